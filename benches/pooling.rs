@@ -0,0 +1,131 @@
+//! Entity churn of bullets and XP orbs, spawned and despawned every frame versus pooled.
+//!
+//! Rapier runs too, so every frame also creates and removes the physics bodies and colliders.
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use criterion::{criterion_group, criterion_main, Criterion};
+use survival::{
+    bullet_plugin::{fire_bullet, release_bullet},
+    components::{Bullet, Damage, LifeTime},
+    pool::{Pool, RegisterPool},
+    xp_plugin::{drop_xp, release_xp, Xp},
+};
+
+/// About a dozen turrets firing for a second.
+const BULLETS_PER_FRAME: usize = 120;
+const ORBS_PER_FRAME: usize = 120;
+
+fn fire_volley(mut cmd: Commands, mut pool: ResMut<Pool<Bullet>>) {
+    for i in 0..BULLETS_PER_FRAME {
+        fire_bullet(
+            &mut cmd,
+            &mut pool,
+            0.0,
+            Vec3::ZERO,
+            Vec3::new(i as f32, 100.0, 0.0),
+            Damage(1.0),
+            LifeTime(30),
+            Handle::default(),
+            1_000.0,
+        );
+    }
+}
+
+fn despawn_bullets(mut cmd: Commands, bullet_q: Query<Entity, With<Bullet>>) {
+    for e in bullet_q.iter() {
+        cmd.entity(e).despawn();
+    }
+}
+
+fn release_bullets(
+    mut cmd: Commands,
+    bullet_q: Query<Entity, With<Bullet>>,
+    mut pool: ResMut<Pool<Bullet>>,
+) {
+    for e in bullet_q.iter() {
+        release_bullet(&mut cmd, &mut pool, e);
+    }
+}
+
+fn drop_orbs(mut cmd: Commands, mut pool: ResMut<Pool<Xp>>) {
+    for i in 0..ORBS_PER_FRAME {
+        drop_xp(
+            &mut cmd,
+            &mut pool,
+            Xp(10.0),
+            Vec2::new(i as f32, 0.0),
+            Vec2::ZERO,
+            Handle::default(),
+        );
+    }
+}
+
+fn despawn_orbs(mut cmd: Commands, xp_q: Query<Entity, With<Xp>>) {
+    for e in xp_q.iter() {
+        cmd.entity(e).despawn();
+    }
+}
+
+fn release_orbs(mut cmd: Commands, xp_q: Query<Entity, With<Xp>>, mut pool: ResMut<Pool<Xp>>) {
+    for e in xp_q.iter() {
+        release_xp(&mut cmd, &mut pool, e);
+    }
+}
+
+fn churn_app<M1, M2>(spawn: impl IntoSystemConfigs<M1>, remove: impl IntoSystemConfigs<M2>) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        HierarchyPlugin,
+        RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(32.0),
+    ))
+    .insert_resource(RapierConfiguration {
+        gravity: Vec2::ZERO,
+        ..default()
+    })
+    .init_pool::<Bullet>()
+    .init_pool::<Xp>()
+    // Removing last frame's entities first lets the new ones reach the physics step.
+    .add_systems(Update, (remove, spawn).chain());
+
+    app.finish();
+    app.cleanup();
+    app
+}
+
+fn bullet_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bullet_churn");
+
+    group.bench_function("spawn_despawn", |b| {
+        let mut app = churn_app(fire_volley, despawn_bullets);
+        b.iter(|| app.update());
+    });
+
+    group.bench_function("pooled", |b| {
+        let mut app = churn_app(fire_volley, release_bullets);
+        b.iter(|| app.update());
+    });
+
+    group.finish();
+}
+
+fn xp_orb_churn(c: &mut Criterion) {
+    let mut group = c.benchmark_group("xp_orb_churn");
+
+    group.bench_function("spawn_despawn", |b| {
+        let mut app = churn_app(drop_orbs, despawn_orbs);
+        b.iter(|| app.update());
+    });
+
+    group.bench_function("pooled", |b| {
+        let mut app = churn_app(drop_orbs, release_orbs);
+        b.iter(|| app.update());
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bullet_churn, xp_orb_churn);
+criterion_main!(benches);
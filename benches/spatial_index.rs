@@ -0,0 +1,110 @@
+//! Radius and nearest queries over 10k entities, brute force versus `SpatialIndex`.
+
+use bevy::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use survival::{components::Enemy, spatial_index::SpatialIndex};
+
+const ENTITIES: usize = 10_000;
+const WORLD_HALF_SIZE: f32 = 10_000.0;
+const QUERIES: usize = 100;
+const RADIUS: f32 = 350.0;
+
+fn random_points(rng: &mut StdRng, count: usize) -> Vec<Vec2> {
+    (0..count)
+        .map(|_| {
+            Vec2::new(
+                rng.gen_range(-WORLD_HALF_SIZE..WORLD_HALF_SIZE),
+                rng.gen_range(-WORLD_HALF_SIZE..WORLD_HALF_SIZE),
+            )
+        })
+        .collect()
+}
+
+fn setup() -> (Vec<(Entity, Vec2)>, SpatialIndex<Enemy>, Vec<Vec2>) {
+    let mut rng = StdRng::seed_from_u64(46);
+    let mut world = World::new();
+
+    let entities: Vec<(Entity, Vec2)> = random_points(&mut rng, ENTITIES)
+        .into_iter()
+        .map(|pos| (world.spawn_empty().id(), pos))
+        .collect();
+
+    let mut index = SpatialIndex::<Enemy>::new(256.0);
+    for (e, pos) in entities.iter() {
+        index.insert(*e, *pos);
+    }
+
+    (entities, index, random_points(&mut rng, QUERIES))
+}
+
+fn radius_query(c: &mut Criterion) {
+    let (entities, index, centers) = setup();
+    let mut group = c.benchmark_group("radius_query_10k");
+
+    group.bench_function("brute_force", |b| {
+        b.iter(|| {
+            for center in centers.iter() {
+                let found = entities
+                    .iter()
+                    .filter(|(_, pos)| pos.distance(*center) <= RADIUS)
+                    .count();
+                black_box(found);
+            }
+        })
+    });
+
+    group.bench_function("spatial_index", |b| {
+        b.iter(|| {
+            for center in centers.iter() {
+                black_box(index.within(*center, RADIUS).count());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn nearest_query(c: &mut Criterion) {
+    let (entities, index, centers) = setup();
+    let mut group = c.benchmark_group("nearest_query_10k");
+
+    group.bench_function("brute_force", |b| {
+        b.iter(|| {
+            for center in centers.iter() {
+                let nearest = entities
+                    .iter()
+                    .filter(|(_, pos)| pos.distance(*center) <= RADIUS)
+                    .min_by(|(_, a), (_, b)| a.distance(*center).total_cmp(&b.distance(*center)));
+                black_box(nearest);
+            }
+        })
+    });
+
+    group.bench_function("spatial_index", |b| {
+        b.iter(|| {
+            for center in centers.iter() {
+                black_box(index.nearest(*center, RADIUS));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn update(c: &mut Criterion) {
+    let (entities, mut index, _) = setup();
+    let mut step = 0.0;
+
+    c.bench_function("spatial_index_move_10k", |b| {
+        b.iter(|| {
+            step += 1.0;
+            for (e, pos) in entities.iter() {
+                index.insert(*e, *pos + Vec2::splat(step));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, radius_query, nearest_query, update);
+criterion_main!(benches);
@@ -3,6 +3,7 @@ use bevy_rapier2d::prelude::*;
 
 use crate::{
     components::Player,
+    status_effect_plugin::{Stat, StatusEffects},
     utils::{chance_one_in, random_in_range, random_vector},
 };
 
@@ -43,25 +44,39 @@ pub enum AggressiveAiState {
 
 fn update_ai(
     player_q: Query<&Transform, With<Player>>,
-    mut ai_q: Query<(&Transform, &mut Velocity, &mut AggressiveAi), Without<Player>>,
+    mut ai_q: Query<
+        (
+            &Transform,
+            &mut Velocity,
+            &mut AggressiveAi,
+            Option<&StatusEffects>,
+        ),
+        Without<Player>,
+    >,
 ) {
     let player = player_q.single();
-    ai_q.iter_mut()
-        .for_each(|(t, mut v, mut a)| ai_tick((t, &mut v, &mut a), player));
+    ai_q.iter_mut().for_each(|(t, mut v, mut a, effects)| {
+        let speed = effects.map_or(1.0, |e| e.multiplier(Stat::MoveSpeed));
+        ai_tick((t, &mut v, &mut a), player, speed)
+    });
 }
 
-fn ai_tick((ai_t, ai_v, ai_a): (&Transform, &mut Velocity, &mut AggressiveAi), p_t: &Transform) {
+fn ai_tick(
+    (ai_t, ai_v, ai_a): (&Transform, &mut Velocity, &mut AggressiveAi),
+    p_t: &Transform,
+    speed_multiplier: f32,
+) {
     let distance_to_player = p_t.translation.distance(ai_t.translation);
 
-    const ATTACK_SPEED: f32 = 80.0;
-    const WANDER_SPEED: f32 = 20.0;
+    let attack_speed = 80.0 * speed_multiplier;
+    let wander_speed = 20.0 * speed_multiplier;
 
     match ai_a.state {
         AggressiveAiState::KillMode => {
             let vector = (p_t.translation - ai_t.translation)
                 .truncate()
                 .normalize_or_zero()
-                * ATTACK_SPEED;
+                * attack_speed;
             ai_v.linvel.x = vector.x;
             ai_v.linvel.y = vector.y;
         }
@@ -72,7 +87,7 @@ fn ai_tick((ai_t, ai_v, ai_a): (&Transform, &mut Velocity, &mut AggressiveAi), p
                 let vector = (p_t.translation - ai_t.translation)
                     .truncate()
                     .normalize_or_zero()
-                    * ATTACK_SPEED;
+                    * attack_speed;
                 ai_v.linvel.x = vector.x;
                 ai_v.linvel.y = vector.y;
             }
@@ -93,7 +108,7 @@ fn ai_tick((ai_t, ai_v, ai_a): (&Transform, &mut Velocity, &mut AggressiveAi), p
                 ai_a.state = AggressiveAiState::Attack;
             }
 
-            let vector = (point - ai_t.translation.truncate()).normalize_or_zero() * WANDER_SPEED;
+            let vector = (point - ai_t.translation.truncate()).normalize_or_zero() * wander_speed;
             ai_v.linvel.x = vector.x;
             ai_v.linvel.y = vector.y;
 
@@ -113,7 +128,7 @@ fn ai_tick((ai_t, ai_v, ai_a): (&Transform, &mut Velocity, &mut AggressiveAi), p
             if distance_to_player < ai_a.view_range {
                 ai_a.state = AggressiveAiState::Attack;
             }
-            let vector = (point - ai_t.translation.truncate()).normalize_or_zero() * ATTACK_SPEED;
+            let vector = (point - ai_t.translation.truncate()).normalize_or_zero() * attack_speed;
             ai_v.linvel.x = vector.x;
             ai_v.linvel.y = vector.y;
 
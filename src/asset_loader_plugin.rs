@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 
-#[derive(Debug, Resource, Clone)]
+/// Handles to every asset the game uses. Headless runs use the default, empty handles.
+#[derive(Debug, Default, Resource, Clone)]
 pub struct AssetLoader {
     pub font: Handle<Font>,
     pub player_sprite: Handle<Image>,
@@ -16,6 +17,11 @@ pub struct AssetLoader {
     pub xp_pickup_sound: Handle<AudioSource>,
     pub pistol_shoot_sound: Handle<AudioSource>,
     pub machine_gun_shoot_sound: Handle<AudioSource>,
+    // PLACEHOLDER: the cannon, flamethrower and slowing tower have no clips of their own yet, these
+    // load other sounds until real ones are added to `assets/`.
+    pub cannon_shoot_sound: Handle<AudioSource>,
+    pub flamethrower_shoot_sound: Handle<AudioSource>,
+    pub slowing_tower_shoot_sound: Handle<AudioSource>,
 
     // Tree
     pub tree_trunk_sprite: Handle<Image>,
@@ -50,6 +56,11 @@ fn init_assets(mut cmd: Commands, asset_server: Res<AssetServer>) {
     let xp_pickup_sound = asset_server.load("xp_pickup.ogg");
     let pistol_shoot_sound = asset_server.load("pistol_fired.ogg");
     let machine_gun_shoot_sound = asset_server.load("machine_gun_fired.ogg");
+    // PLACEHOLDER: borrowed clips, replace with "cannon_fired.ogg", "flamethrower_fired.ogg" and
+    // "slowing_tower_fired.ogg" once those exist.
+    let cannon_shoot_sound = asset_server.load("pistol_fired.ogg");
+    let flamethrower_shoot_sound = asset_server.load("machine_gun_fired.ogg");
+    let slowing_tower_shoot_sound = asset_server.load("xp_pickup.ogg");
 
     // Tree
     let tree_trunk_sprite = asset_server.load("tree-trunk.png");
@@ -75,6 +86,9 @@ fn init_assets(mut cmd: Commands, asset_server: Res<AssetServer>) {
         xp_pickup_sound,
         pistol_shoot_sound,
         machine_gun_shoot_sound,
+        cannon_shoot_sound,
+        flamethrower_shoot_sound,
+        slowing_tower_shoot_sound,
         tree_trunk_sprite,
         tree_main_sprite,
         attack_tree_sound,
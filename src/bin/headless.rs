@@ -0,0 +1,201 @@
+//! Runs the gameplay without a window, audio or input devices for a number of simulated minutes
+//! at a fixed 60 frames per second and prints the run stats as RON. The run uses the default
+//! configs and a fixed biome seed.
+//!
+//! ```sh
+//! cargo run --release --bin headless -- --minutes 10 --player auto
+//! ```
+
+use bevy::{app::AppExit, prelude::*};
+use serde::Serialize;
+
+use survival::{
+    components::{Enemy, Gathering, Inventory, ItemType, Player},
+    difficulty_plugin::Difficulty,
+    headless_app,
+    input_map_plugin::{ActionState, InputAction},
+    run_stats_plugin::RunStats,
+    spatial_index::SpatialIndex,
+    tree_plugin::Tree,
+    turret_plugin::{turret_types::TurretTypes, SelectedTurretType},
+    xp_plugin::xp_level::XpLevel,
+};
+
+const FRAMES_PER_SECOND: u32 = 60;
+
+/// Enemies closer than this get shot at.
+const FIGHT_RANGE: f32 = 350.0;
+/// Enemies closer than this get run away from.
+const FLEE_RANGE: f32 = 120.0;
+/// How far the scripted player looks for a tree to chop.
+const TREE_SEARCH_RANGE: f32 = 2_000.0;
+/// Wood kept back after building a turret so the scripted player can still repair.
+const WOOD_RESERVE: u32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+enum ScriptedPlayer {
+    /// Stands still and does nothing, for measuring how fast an untouched run goes.
+    Idle,
+    /// Shoots the closest enemy, runs from the ones too close, chops trees and builds turrets.
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, Resource)]
+struct Script(ScriptedPlayer);
+
+struct Options {
+    minutes: f32,
+    player: ScriptedPlayer,
+}
+
+impl Options {
+    fn from_args() -> Result<Self, String> {
+        let mut options = Self {
+            minutes: 5.0,
+            player: ScriptedPlayer::Idle,
+        };
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+            match arg.as_str() {
+                "--minutes" => {
+                    options.minutes = value()?
+                        .parse()
+                        .map_err(|e| format!("invalid --minutes: {e}"))?;
+                }
+                "--player" => {
+                    options.player = match value()?.as_str() {
+                        "idle" => ScriptedPlayer::Idle,
+                        "auto" => ScriptedPlayer::Auto,
+                        other => return Err(format!("unknown player {other}, use idle or auto")),
+                    };
+                }
+                other => return Err(format!("unknown argument {other}")),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    player: ScriptedPlayer,
+    simulated_secs: f32,
+    survived: bool,
+    survival_secs: f32,
+    score: u32,
+    kills: u32,
+    trees_chopped: u32,
+    items_picked_up: u32,
+    turrets_built: u32,
+    xp_earned: f32,
+    player_level: u32,
+    difficulty_level: u32,
+    enemies_alive: usize,
+}
+
+fn main() {
+    let options = match Options::from_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("usage: headless [--minutes N] [--player idle|auto]");
+            std::process::exit(2);
+        }
+    };
+
+    let mut app = headless_app(FRAMES_PER_SECOND);
+    app.insert_resource(Script(options.player))
+        .add_systems(PreUpdate, drive_player);
+
+    let frames = (options.minutes * 60.0 * FRAMES_PER_SECOND as f32) as u32;
+    let mut simulated_frames = 0;
+    for _ in 0..frames {
+        app.update();
+        simulated_frames += 1;
+
+        if app.world.resource::<RunStats>().finished
+            || !app.world.resource::<Events<AppExit>>().is_empty()
+        {
+            break;
+        }
+    }
+
+    let report = report(&mut app, options.player, simulated_frames);
+    println!(
+        "{}",
+        ron::ser::to_string_pretty(&report, ron::ser::PrettyConfig::default())
+            .expect("report serializes")
+    );
+}
+
+fn report(app: &mut App, player: ScriptedPlayer, simulated_frames: u32) -> Report {
+    let stats = app.world.resource::<RunStats>().clone();
+    let player_level = app
+        .world
+        .query_filtered::<&XpLevel, With<Player>>()
+        .get_single(&app.world)
+        .map_or(0, |lvl| lvl.level);
+
+    Report {
+        player,
+        simulated_secs: simulated_frames as f32 / FRAMES_PER_SECOND as f32,
+        survived: !stats.finished,
+        survival_secs: stats.survival_secs,
+        score: stats.score as u32,
+        kills: stats.kills,
+        trees_chopped: stats.trees_chopped,
+        items_picked_up: stats.items_picked_up,
+        turrets_built: stats.turrets_built,
+        xp_earned: stats.xp_earned,
+        player_level,
+        difficulty_level: app.world.resource::<Difficulty>().level,
+        enemies_alive: app.world.resource::<SpatialIndex<Enemy>>().len(),
+    }
+}
+
+/// Stands in for `InputMapPlugin`, fills the `ActionState` from the script every frame.
+fn drive_player(
+    mut actions: ResMut<ActionState>,
+    script: Res<Script>,
+    player_q: Query<(&Transform, &Gathering, &Inventory), With<Player>>,
+    enemies: Res<SpatialIndex<Enemy>>,
+    trees: Res<SpatialIndex<Tree>>,
+    selected: Res<SelectedTurretType>,
+    turret_types: Res<TurretTypes>,
+) {
+    actions.release_all();
+
+    if script.0 == ScriptedPlayer::Idle {
+        return;
+    }
+
+    let Ok((transform, gathering, inventory)) = player_q.get_single() else {
+        return;
+    };
+    let pos = transform.translation.truncate();
+
+    if let Some((_, enemy)) = enemies.nearest(pos, FIGHT_RANGE) {
+        actions.aim_at(enemy);
+        actions.press(InputAction::Fire);
+
+        if pos.distance(enemy) < FLEE_RANGE {
+            actions.movement = (pos - enemy).normalize_or_zero();
+        }
+    } else if let Some((_, tree)) = trees.nearest(pos, TREE_SEARCH_RANGE) {
+        actions.aim_at(tree);
+        if pos.distance(tree) <= gathering.range * 0.75 {
+            actions.press(InputAction::Gather);
+        } else {
+            actions.movement = (tree - pos).normalize_or_zero();
+        }
+    }
+
+    if let Some(def) = turret_types.get(selected.0) {
+        if inventory.count(ItemType::Wood) >= def.cost + WOOD_RESERVE {
+            actions.press(InputAction::Build);
+        }
+    }
+}
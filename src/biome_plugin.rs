@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use noise::{NoiseFn, Perlin};
+use serde::{Deserialize, Serialize};
+
+/// Roughly how wide a biome is, in world units.
+const BIOME_SCALE: f64 = 2_500.0;
+/// Noise above this is forest, about a quarter of the map.
+const FOREST_ABOVE: f64 = 0.35;
+/// Noise below this is wasteland, about a fifth of the map.
+const WASTELAND_BELOW: f64 = -0.38;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub enum Biome {
+    Meadow,
+    Forest,
+    Wasteland,
+}
+
+/// Splits the map into biomes with low frequency noise, a new seed every run.
+#[derive(Debug, Clone, Copy, Resource)]
+pub struct BiomeMap {
+    noise: Perlin,
+}
+
+impl Default for BiomeMap {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl BiomeMap {
+    pub fn new(seed: u32) -> Self {
+        Self {
+            noise: Perlin::new(seed),
+        }
+    }
+
+    pub fn biome_at(&self, pos: Vec2) -> Biome {
+        let value = self
+            .noise
+            .get([pos.x as f64 / BIOME_SCALE, pos.y as f64 / BIOME_SCALE]);
+
+        if value > FOREST_ABOVE {
+            Biome::Forest
+        } else if value < WASTELAND_BELOW {
+            Biome::Wasteland
+        } else {
+            Biome::Meadow
+        }
+    }
+}
+
+pub struct BiomePlugin;
+
+impl Plugin for BiomePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BiomeMap>();
+        app.register_type::<Biome>();
+    }
+}
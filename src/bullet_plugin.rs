@@ -1,18 +1,67 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     asset_loader_plugin::AssetLoader,
-    components::{Bullet, Damage, LifeTime},
+    collision_layers,
+    components::{Bullet, Damage, Enemy, LifeTime},
+    config::InitConfig,
+    pool::{Pool, RegisterPool},
+    spatial_index::SpatialIndex,
+    status_effect_plugin::StatusEffect,
     utils::random_vector,
 };
 
+const PROJECTILE_CONFIG_FILE: &str = "projectiles.ron";
+/// How far a homing bullet looks for something to steer towards.
+const HOMING_RANGE: f32 = 400.0;
+/// Frames the puff left behind by a blocked bullet lasts.
+const IMPACT_FRAMES: u32 = 12;
+
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectileConfig {
+    /// Share of a bullet's damage a tree takes when shot, 0 keeps trees bulletproof.
+    pub tree_damage_multiplier: f32,
+}
+
+impl Default for ProjectileConfig {
+    fn default() -> Self {
+        Self {
+            tree_damage_multiplier: 0.25,
+        }
+    }
+}
+
+/// A bullet ran into solid world geometry and stopped there.
+#[derive(Debug, Clone, Event)]
+pub struct ProjectileBlockedEvent {
+    pub obstacle: Entity,
+    pub at: Vec2,
+    pub damage: Damage,
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+struct Impact;
+
 pub struct BulletPlugin;
 
 impl Plugin for BulletPlugin {
     fn build(&self, app: &mut App) {
+        app.init_config::<ProjectileConfig>(PROJECTILE_CONFIG_FILE);
+        app.init_pool::<Bullet>();
         app.add_event::<BulletFiredEvent>();
-        app.add_systems(Update, handle_bullet_fired_event);
+        app.add_event::<ProjectileBlockedEvent>();
+        app.add_systems(
+            Update,
+            (
+                handle_bullet_fired_event,
+                steer_homing_bullets,
+                bullet_world_collision,
+                animate_impacts,
+            ),
+        );
     }
 }
 
@@ -24,13 +73,81 @@ pub struct BulletFiredEvent {
     pub dmg: Damage,
     pub lifetime: LifeTime,
     pub bullet_speed: f32,
+    pub modifiers: ProjectileModifiers,
 }
 
 #[derive(Debug, Clone, Copy, Component)]
 pub struct OriginPosition(pub Vec2);
 
+/// Enemies this bullet already hit, so piercing and bouncing never hit the same one twice.
+#[derive(Debug, Clone, Default, Component)]
+pub struct HitTargets(pub Vec<Entity>);
+
+/// Behaviours a projectile is fired with. Weapons carry a set, level-up perks add to the player's.
+#[derive(Debug, Clone, Default, Component)]
+pub struct ProjectileModifiers {
+    /// Enemies the bullet passes through before stopping.
+    pub pierce: u32,
+    /// Times the bullet bounces on to the nearest other enemy.
+    pub ricochet: u32,
+    /// Turn rate towards the nearest enemy, in radians per second.
+    pub homing: Option<f32>,
+    pub splash: Option<SplashDamage>,
+    /// Smaller bullets fanned out from the first hit.
+    pub split: u32,
+    pub on_hit: Option<StatusEffect>,
+}
+
+impl ProjectileModifiers {
+    pub fn attach(&self, bullet: &mut EntityCommands) {
+        if self.pierce > 0 {
+            bullet.insert(Pierce(self.pierce));
+        }
+        if self.ricochet > 0 {
+            bullet.insert(Ricochet(self.ricochet));
+        }
+        if let Some(turn_rate) = self.homing {
+            bullet.insert(Homing(turn_rate));
+        }
+        if let Some(splash) = self.splash {
+            bullet.insert(splash);
+        }
+        if self.split > 0 {
+            bullet.insert(Split(self.split));
+        }
+        if let Some(effect) = &self.on_hit {
+            bullet.insert(OnHitEffect(effect.clone()));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Pierce(pub u32);
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Ricochet(pub u32);
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Homing(pub f32);
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Split(pub u32);
+
+/// Damage dealt to every other enemy within `radius` of the hit.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct SplashDamage {
+    pub radius: f32,
+    pub damage: f32,
+}
+
+/// Status effect applied to the enemy the bullet hits.
+#[derive(Debug, Clone, Component)]
+pub struct OnHitEffect(pub StatusEffect);
+
+/// Reuses a parked bullet from `pool` when there is one, spawns a new one otherwise.
 pub fn fire_bullet(
     cmd: &mut Commands,
+    pool: &mut Pool<Bullet>,
     accuracy: f32,
     originates_from: Vec3,
     shoot_at: Vec3,
@@ -38,23 +155,34 @@ pub fn fire_bullet(
     lifetime: LifeTime,
     texture: Handle<Image>,
     bullet_speed: f32,
-) {
+) -> Entity {
     let acc_skew = random_vector() * accuracy;
     let vel = (shoot_at + acc_skew - originates_from)
         .truncate()
         .normalize()
         * bullet_speed;
 
-    cmd.spawn((
+    let shot = (
         Bullet,
         OriginPosition(originates_from.truncate()),
+        HitTargets::default(),
+        Velocity::linear(vel),
+        damage,
+        lifetime,
+    );
+
+    if let Some(mut bullet) = pool.acquire(cmd) {
+        bullet.insert((shot, Transform::from_translation(originates_from), texture));
+        return bullet.id();
+    }
+
+    cmd.spawn((
+        shot,
         RigidBody::Dynamic,
         Sensor,
         Collider::ball(2.0),
+        collision_layers::projectile(),
         ActiveEvents::COLLISION_EVENTS,
-        Velocity::linear(vel),
-        damage,
-        lifetime,
         SpriteBundle {
             transform: Transform::from_translation(originates_from),
             texture,
@@ -65,17 +193,35 @@ pub fn fire_bullet(
             ..default()
         },
         Name::new("Bullet"),
-    ));
+    ))
+    .id()
+}
+
+/// Parks a bullet in `pool` instead of despawning it, stripped of its lifetime and modifiers.
+pub fn release_bullet(cmd: &mut Commands, pool: &mut Pool<Bullet>, bullet: Entity) {
+    if let Some(mut bullet) = pool.release(cmd, bullet) {
+        bullet.remove::<(
+            LifeTime,
+            Pierce,
+            Ricochet,
+            Homing,
+            SplashDamage,
+            Split,
+            OnHitEffect,
+        )>();
+    }
 }
 
 fn handle_bullet_fired_event(
     mut cmd: Commands,
     mut ev: EventReader<BulletFiredEvent>,
+    mut pool: ResMut<Pool<Bullet>>,
     asset_loader: Res<AssetLoader>,
 ) {
     for e in ev.read() {
-        fire_bullet(
+        let bullet = fire_bullet(
             &mut cmd,
+            &mut pool,
             e.acc,
             e.from.extend(10.0),
             e.at.extend(10.0),
@@ -84,7 +230,90 @@ fn handle_bullet_fired_event(
             asset_loader.bullet_sprite.clone(),
             e.bullet_speed,
         );
+
+        e.modifiers.attach(&mut cmd.entity(bullet));
     }
 
     ev.clear();
 }
+
+fn steer_homing_bullets(
+    mut bullet_q: Query<(&Transform, &Homing, &mut Velocity), With<Bullet>>,
+    enemies: Res<SpatialIndex<Enemy>>,
+    time: Res<Time>,
+) {
+    for (bt, homing, mut vel) in bullet_q.iter_mut() {
+        let pos = bt.translation.truncate();
+        let target = enemies.nearest(pos, HOMING_RANGE);
+
+        let Some(to_target) = target.map(|(_, t)| t - pos).filter(|d| *d != Vec2::ZERO) else {
+            continue;
+        };
+
+        let max_turn = homing.0 * time.delta_seconds();
+        let turn = vel
+            .linvel
+            .angle_between(to_target)
+            .clamp(-max_turn, max_turn);
+        vel.linvel = Vec2::from_angle(turn).rotate(vel.linvel);
+    }
+}
+
+/// Anything a projectile collides with that isn't an enemy is part of the world and stops it.
+fn bullet_world_collision(
+    mut cmd: Commands,
+    bullet_q: Query<(&Transform, &Damage), With<Bullet>>,
+    enemy_q: Query<(), With<Enemy>>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut blocked_events: EventWriter<ProjectileBlockedEvent>,
+    mut pool: ResMut<Pool<Bullet>>,
+    assets: Res<AssetLoader>,
+) {
+    for collision_event in collision_events.read() {
+        if let CollisionEvent::Started(a, b, _f) = collision_event {
+            let (bullet, obstacle) = if bullet_q.contains(*a) {
+                (*a, *b)
+            } else {
+                (*b, *a)
+            };
+
+            if enemy_q.contains(obstacle) || bullet_q.contains(obstacle) {
+                continue;
+            }
+
+            let Ok((bt, dmg)) = bullet_q.get(bullet) else {
+                continue;
+            };
+
+            release_bullet(&mut cmd, &mut pool, bullet);
+            cmd.spawn((
+                Impact,
+                LifeTime(IMPACT_FRAMES),
+                SpriteBundle {
+                    transform: Transform::from_translation(bt.translation),
+                    texture: assets.bullet_sprite.clone(),
+                    sprite: Sprite {
+                        custom_size: Some(Vec2::splat(12.0)),
+                        ..default()
+                    },
+                    ..default()
+                },
+                Name::new("Impact"),
+            ));
+
+            blocked_events.send(ProjectileBlockedEvent {
+                obstacle,
+                at: bt.translation.truncate(),
+                damage: *dmg,
+            });
+        }
+    }
+}
+
+fn animate_impacts(mut impact_q: Query<(&LifeTime, &mut Transform, &mut Sprite), With<Impact>>) {
+    for (lifetime, mut t, mut sprite) in impact_q.iter_mut() {
+        let left = lifetime.0 as f32 / IMPACT_FRAMES as f32;
+        t.scale = Vec3::splat(2.0 - left);
+        sprite.color.set_a(left);
+    }
+}
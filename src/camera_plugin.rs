@@ -18,7 +18,6 @@ impl Plugin for CameraPlugin {
         app.add_systems(Startup, create_camera);
         app.add_systems(Update, (follow_player, update_mouse_screen_pos));
         app.init_resource::<MousePosition>();
-        app.init_resource::<MouseHighlightedAction>();
     }
 }
 
@@ -0,0 +1,29 @@
+use bevy_rapier2d::prelude::*;
+
+pub const PLAYER: Group = Group::GROUP_1;
+pub const ENEMY: Group = Group::GROUP_2;
+pub const WORLD: Group = Group::GROUP_3;
+pub const PROJECTILE: Group = Group::GROUP_4;
+pub const BUILDING: Group = Group::GROUP_5;
+
+pub fn player() -> CollisionGroups {
+    CollisionGroups::new(PLAYER, ENEMY | WORLD | BUILDING)
+}
+
+pub fn enemy() -> CollisionGroups {
+    CollisionGroups::new(ENEMY, PLAYER | ENEMY | WORLD | BUILDING | PROJECTILE)
+}
+
+/// Trees and anything else that stands still and blocks both movement and shots.
+pub fn world() -> CollisionGroups {
+    CollisionGroups::new(WORLD, PLAYER | ENEMY | PROJECTILE)
+}
+
+/// Turrets and other things the player builds, they block movement but get shot over.
+pub fn building() -> CollisionGroups {
+    CollisionGroups::new(BUILDING, PLAYER | ENEMY)
+}
+
+pub fn projectile() -> CollisionGroups {
+    CollisionGroups::new(PROJECTILE, ENEMY | WORLD)
+}
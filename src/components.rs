@@ -1,4 +1,4 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
 #[derive(Debug, Clone, Component)]
 pub struct Player;
@@ -24,6 +24,15 @@ pub struct UiLevelDisplayBar;
 #[derive(Debug, Clone, Copy, Component, Reflect)]
 pub struct Health(pub f32, pub f32);
 
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct Stamina(pub f32, pub f32);
+
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct Hunger(pub f32, pub f32);
+
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct Thirst(pub f32, pub f32);
+
 #[derive(Debug, Clone, Copy, Component, Reflect)]
 pub struct Damage(pub f32);
 
@@ -39,12 +48,37 @@ pub struct IFrames(pub f32);
 #[derive(Debug, Clone, Copy, Default, Component, Reflect)]
 pub struct LifeTime(pub u32);
 
+/// Contact attack enemies use against buildings.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct MeleeAttack {
+    pub damage: f32,
+    pub range: f32,
+    pub cooldown: Timer,
+}
+
+impl MeleeAttack {
+    pub fn new(damage: f32, range: f32, cooldown_secs: f32) -> Self {
+        Self {
+            damage,
+            range,
+            cooldown: Timer::from_seconds(cooldown_secs, TimerMode::Once),
+        }
+    }
+}
+
+/// XP dropped when this entity dies, before multipliers.
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct XpReward(pub f32);
+
 #[derive(Debug, Clone, Copy, Default, Component)]
 pub struct AttractedToPlayer;
 
 #[derive(Debug, Clone, Copy, Default, Component)]
 pub struct Magnet;
 
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct WaterSource;
+
 #[derive(Debug, Clone, Copy, Default, Component, Reflect)]
 pub struct Gathering {
     pub damage: f32,
@@ -54,6 +88,78 @@ pub struct Gathering {
 
 #[derive(Debug, Clone, Copy, Component, Reflect)]
 pub enum PickupType {
+    /// The amount is read from the `Xp` component on the pickup.
+    Xp,
+    Item(ItemType),
+}
+
+/// Pull speed towards the player, from `near` right next to them to `far` at the edge of the attract range.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub struct SpeedCurve {
+    pub near: f32,
+    pub far: f32,
+}
+
+impl SpeedCurve {
+    pub fn speed(&self, distance_fraction: f32) -> f32 {
+        self.near + (self.far - self.near) * distance_fraction.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct Attractable {
+    pub speed_curve: SpeedCurve,
+}
+
+impl Default for Attractable {
+    fn default() -> Self {
+        Self {
+            speed_curve: SpeedCurve {
+                near: 400.0,
+                far: 150.0,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum ItemType {
+    Wood,
+    Sapling,
+    Berries,
+    Water,
     Magnet,
-    Item,
+    HealthPotion,
+    DamageBoost,
+    PickupBoost,
+}
+
+#[derive(Debug, Clone, Default, Component, Reflect)]
+pub struct Inventory {
+    items: HashMap<ItemType, u32>,
+}
+
+impl Inventory {
+    pub fn add(&mut self, item: ItemType, count: u32) {
+        *self.items.entry(item).or_default() += count;
+    }
+
+    pub fn count(&self, item: ItemType) -> u32 {
+        self.items.get(&item).copied().unwrap_or_default()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (ItemType, u32)> + '_ {
+        self.items.iter().map(|(item, count)| (*item, *count))
+    }
+
+    /// Removes `count` items only if there are enough of them.
+    pub fn take(&mut self, item: ItemType, count: u32) -> bool {
+        match self.items.get_mut(&item) {
+            Some(have) if *have >= count => {
+                *have -= count;
+                true
+            }
+            _ => false,
+        }
+    }
 }
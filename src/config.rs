@@ -0,0 +1,64 @@
+use std::{fs, path::Path};
+
+use bevy::{
+    log::{info, warn},
+    prelude::{App, Resource},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+pub const CONFIG_DIR: &str = "config";
+
+/// Reads `config/<file_name>`, falling back to the defaults when it's missing or broken.
+/// A missing file gets created so there is something to tweak.
+pub fn load_or_default<T: Serialize + DeserializeOwned + Default>(file_name: &str) -> T {
+    let path = Path::new(CONFIG_DIR).join(file_name);
+
+    match fs::read_to_string(&path) {
+        Ok(content) => ron::from_str(&content).unwrap_or_else(|err| {
+            warn!("Failed to parse {}: {err}, using defaults", path.display());
+            T::default()
+        }),
+        Err(_) => {
+            let config = T::default();
+            save(file_name, &config);
+            config
+        }
+    }
+}
+
+pub fn save<T: Serialize>(file_name: &str, value: &T) {
+    let path = Path::new(CONFIG_DIR).join(file_name);
+
+    let result = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+        .map_err(|err| err.to_string())
+        .and_then(|content| {
+            fs::create_dir_all(CONFIG_DIR)
+                .and_then(|_| fs::write(&path, content))
+                .map_err(|err| err.to_string())
+        });
+
+    match result {
+        Ok(_) => info!("Saved {}", path.display()),
+        Err(err) => warn!("Failed to save {}: {err}", path.display()),
+    }
+}
+
+pub trait InitConfig {
+    /// Inserts `T` read from `config/<file_name>`, unless the app already has one.
+    fn init_config<T: Resource + Serialize + DeserializeOwned + Default>(
+        &mut self,
+        file_name: &str,
+    ) -> &mut Self;
+}
+
+impl InitConfig for App {
+    fn init_config<T: Resource + Serialize + DeserializeOwned + Default>(
+        &mut self,
+        file_name: &str,
+    ) -> &mut Self {
+        if !self.world.contains_resource::<T>() {
+            self.insert_resource(load_or_default::<T>(file_name));
+        }
+        self
+    }
+}
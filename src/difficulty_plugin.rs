@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+
+/// Seconds of survival needed to raise the difficulty by one level.
+const SECONDS_PER_LEVEL: f32 = 60.0;
+
+/// Difficulty that rises the longer the run goes on.
+#[derive(Debug, Resource)]
+pub struct Difficulty {
+    pub level: u32,
+    timer: Timer,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self {
+            level: 0,
+            timer: Timer::from_seconds(SECONDS_PER_LEVEL, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Difficulty {
+    /// Harder runs pay out more XP, 10% per level.
+    pub fn xp_multiplier(&self) -> f32 {
+        1.0 + self.level as f32 * 0.1
+    }
+}
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Difficulty>();
+        app.add_systems(Update, raise_difficulty);
+    }
+}
+
+fn raise_difficulty(mut difficulty: ResMut<Difficulty>, time: Res<Time>) {
+    let levels = difficulty
+        .timer
+        .tick(time.delta())
+        .times_finished_this_tick();
+
+    difficulty.level += levels;
+}
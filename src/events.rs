@@ -1,8 +1,8 @@
 use bevy::prelude::*;
 
-use crate::components::PickupType;
+use crate::components::{ItemType, PickupType};
 
-#[derive(Debug, Event)]
+#[derive(Debug, Clone, Copy, Event)]
 pub enum SoundEvent {
     Damage,
     Death,
@@ -11,12 +11,13 @@ pub enum SoundEvent {
     TreeHitGround,
     PistolShoot,
     MachineGunShoot,
+    CannonShoot,
+    FlamethrowerShoot,
+    SlowingTowerShoot,
 }
 
 #[derive(Debug, Clone, Event)]
-pub enum ItemDropEvent {
-    Wood(u32, Vec2),
-}
+pub struct ItemDropEvent(pub ItemType, pub u32, pub Vec2);
 
 #[derive(Debug, Event)]
 pub struct TreeDiedEvent(pub Entity, pub Vec3, pub f32);
@@ -24,5 +25,23 @@ pub struct TreeDiedEvent(pub Entity, pub Vec3, pub f32);
 #[derive(Debug, Default, Event, Clone)]
 pub struct XpDropEvent(pub Vec3, pub f32);
 
+#[derive(Debug, Event, Clone)]
+pub struct EnemyKilledEvent;
+
 #[derive(Debug, Event, Clone)]
 pub struct PickupTakenEvent(pub Entity, pub PickupType);
+
+#[derive(Debug, Clone, Event)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+    /// Where the hit came from, `None` for damage over time.
+    pub from: Option<Vec2>,
+}
+
+/// Player actions that cost survival stats.
+#[derive(Debug, Clone, Copy, Event)]
+pub enum ActivityEvent {
+    Chop,
+    Dash,
+}
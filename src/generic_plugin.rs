@@ -3,44 +3,59 @@ use std::ops::Sub;
 use crate::{
     aggressive_ai_plugin::{AggressiveAi, AggressiveAiState},
     asset_loader_plugin::AssetLoader,
-    bullet_plugin::OriginPosition,
+    bullet_plugin::{
+        fire_bullet, release_bullet, HitTargets, OnHitEffect, OriginPosition, Pierce, Ricochet,
+        SplashDamage, Split,
+    },
     components::{
-        AttractedToPlayer, Bullet, Damage, Enemy, Gathering, Health, IFrames, LifeTime,
-        PickupRange, PickupType, Player, PlayerPickup,
+        Bullet, Damage, Enemy, Gathering, Health, IFrames, Inventory, ItemType, LifeTime,
+        PickupRange, PickupType, Player, PlayerPickup, Stamina, XpReward,
     },
-    events::{ItemDropEvent, SoundEvent, XpDropEvent},
-    spawner_plugin::{SpawnedEntiyDeathEvent, SpawnerId},
-    tree_plugin::drop_wood,
+    events::{DamageEvent, EnemyKilledEvent, ItemDropEvent, SoundEvent, XpDropEvent},
+    pickup_plugin::drop_item,
+    pool::Pool,
+    spatial_index::SpatialIndex,
+    status_effect_plugin::ApplyStatusEffectEvent,
+    utils::chance_one_in,
 };
-use bevy::{audio::Volume, prelude::*};
+use bevy::prelude::*;
 use bevy_rapier2d::prelude::*;
 
+/// How far a bouncing bullet looks for the next enemy.
+const RICOCHET_RANGE: f32 = 300.0;
+/// Frames the shards of a split bullet live for.
+const SPLIT_LIFETIME: u32 = 30;
+
 pub struct GenericPlugin;
 
 impl Plugin for GenericPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<SoundEvent>();
         app.add_event::<ItemDropEvent>();
+        app.add_event::<DamageEvent>();
         app.add_systems(
             Update,
             (
                 tick_iframes,
                 tick_lifetimes,
                 bullet_enemy_collision,
-                play_sound_event,
+                handle_damage_event,
                 tick_gathering,
-                attract_all_items,
                 handle_item_drop_event,
             ),
         );
         app.register_type::<PlayerPickup>();
         app.register_type::<Health>();
+        app.register_type::<Stamina>();
         app.register_type::<PickupRange>();
         app.register_type::<Damage>();
         app.register_type::<IFrames>();
         app.register_type::<LifeTime>();
         app.register_type::<Gathering>();
         app.register_type::<PickupType>();
+        app.register_type::<ItemType>();
+        app.register_type::<Inventory>();
+        app.register_type::<XpReward>();
     }
 }
 
@@ -49,46 +64,37 @@ fn handle_item_drop_event(
     mut drop_events: EventReader<ItemDropEvent>,
     assets: Res<AssetLoader>,
 ) {
-    for ev in drop_events.read() {
-        match ev {
-            ItemDropEvent::Wood(count, point) => {
-                drop_wood(&mut cmd, *point, *count, assets.item_wood_sprite.clone());
-            }
-        }
+    for ItemDropEvent(item, count, point) in drop_events.read() {
+        drop_item(&mut cmd, *item, *point, *count, &assets);
     }
     drop_events.clear();
 }
 
-fn attract_all_items(
-    player_p: Query<&Transform, With<Player>>,
-    mut xp_q: Query<(&Transform, &mut Velocity), (With<AttractedToPlayer>, Without<Player>)>,
-) {
-    let player = player_p.single();
-    for (t, mut v) in xp_q.iter_mut() {
-        const ATTRACT_SPEED: f32 = 1000.0;
-
-        let vector = (player.translation - t.translation).normalize_or_zero();
-
-        v.linvel.x = vector.x * ATTRACT_SPEED;
-        v.linvel.y = vector.y * ATTRACT_SPEED;
-    }
-}
-
 fn tick_iframes(mut query: Query<(&mut IFrames, &mut Sprite)>) {
     for (mut iframes, mut sprite) in query.iter_mut() {
         iframes.0 = (iframes.0 - 0.01).max(0.0);
 
-        sprite.color.set_g(1.0 - iframes.0 * 5.0);
-        sprite.color.set_b(1.0 - iframes.0 * 5.0);
+        // Dash iframes last longer than a hit, the flash only covers the last 0.2 seconds.
+        let flash = (1.0 - iframes.0 * 5.0).max(0.0);
+        sprite.color.set_g(flash);
+        sprite.color.set_b(flash);
     }
 }
 
-fn tick_lifetimes(mut cmd: Commands, mut query: Query<(&mut LifeTime, Entity)>) {
-    for (mut l, e) in query.iter_mut() {
+fn tick_lifetimes(
+    mut cmd: Commands,
+    mut query: Query<(&mut LifeTime, Entity, Has<Bullet>)>,
+    mut bullet_pool: ResMut<Pool<Bullet>>,
+) {
+    for (mut l, e, is_bullet) in query.iter_mut() {
         l.0 = l.0.saturating_sub(1);
 
         if l.0 == 0 {
-            cmd.entity(e).despawn();
+            if is_bullet {
+                release_bullet(&mut cmd, &mut bullet_pool, e);
+            } else {
+                cmd.entity(e).despawn();
+            }
         }
     }
 }
@@ -99,101 +105,186 @@ fn tick_gathering(mut query: Query<&mut Gathering>) {
     }
 }
 
-fn play_sound_event(
-    mut cmd: Commands,
-    mut sound_event: EventReader<SoundEvent>,
-    asset_loader: Res<AssetLoader>,
-) {
-    for ev in sound_event.read() {
-        let (sound, volume) = match ev {
-            SoundEvent::Damage => (asset_loader.damage_sound.clone(), 0.1),
-            SoundEvent::Death => (asset_loader.death_sound.clone(), 0.005),
-            SoundEvent::XpPickup => (asset_loader.xp_pickup_sound.clone(), 0.3),
-            SoundEvent::AttackTree => (asset_loader.attack_tree_sound.clone(), 0.5),
-            SoundEvent::TreeHitGround => (asset_loader.tree_hit_ground_sound.clone(), 0.7),
-            SoundEvent::PistolShoot => (asset_loader.pistol_shoot_sound.clone(), 0.5),
-            SoundEvent::MachineGunShoot => (asset_loader.machine_gun_shoot_sound.clone(), 0.2),
-        };
-
-        cmd.spawn(AudioBundle {
-            source: sound,
-            settings: PlaybackSettings {
-                mode: bevy::audio::PlaybackMode::Despawn,
-                volume: Volume::new(volume),
-                ..Default::default()
-            },
-        });
-    }
-    sound_event.clear();
-}
-
-// I don't even...
 fn bullet_enemy_collision(
     mut cmd: Commands,
-    mut enemy_q: Query<
+    enemy_q: Query<(), With<Enemy>>,
+    enemies: Res<SpatialIndex<Enemy>>,
+    mut bullet_q: Query<
         (
             &Transform,
-            &mut Health,
-            &mut IFrames,
-            &SpawnerId,
-            &mut AggressiveAi,
-            Entity,
+            &Damage,
+            &OriginPosition,
+            &mut Velocity,
+            &mut HitTargets,
+            Option<&mut Pierce>,
+            Option<&mut Ricochet>,
+            Option<&SplashDamage>,
+            Option<&Split>,
+            Option<&OnHitEffect>,
         ),
-        With<Enemy>,
+        (With<Bullet>, Without<Enemy>),
     >,
-    bullet_q: Query<(&Damage, &OriginPosition, Entity), (With<Bullet>, Without<Enemy>)>,
     mut collision_events: EventReader<CollisionEvent>,
-    mut sound_event: EventWriter<SoundEvent>,
-    mut xp_event: EventWriter<XpDropEvent>,
-    mut entity_death_event: EventWriter<SpawnedEntiyDeathEvent>,
+    mut damage_event: EventWriter<DamageEvent>,
+    mut effect_event: EventWriter<ApplyStatusEffectEvent>,
+    mut pool: ResMut<Pool<Bullet>>,
+    assets: Res<AssetLoader>,
 ) {
     for collision_event in collision_events.read() {
         if let CollisionEvent::Started(a, b, _f) = collision_event {
-            for (transform, mut hp, mut iframes, sid, mut ai, entity) in enemy_q.iter_mut() {
-                if a == &entity {
-                    for (dmg, o, bullet_e) in bullet_q.iter() {
-                        if &bullet_e == b {
-                            hp.0 = hp.0.sub(dmg.0);
-                            iframes.0 = 0.2;
-                            cmd.entity(bullet_e).despawn();
-
-                            if ai.state != AggressiveAiState::KillMode {
-                                ai.state = AggressiveAiState::CheckLocation(o.0);
-                            }
-
-                            if hp.0 <= 0.0 {
-                                cmd.entity(entity).despawn();
-                                entity_death_event.send(SpawnedEntiyDeathEvent(sid.0));
-                                sound_event.send(SoundEvent::Death);
-                                xp_event.send(XpDropEvent(transform.translation, 10.));
-                            } else {
-                                sound_event.send(SoundEvent::Damage);
-                            }
-                        }
-                    }
-                } else if b == &entity {
-                    for (dmg, o, bullet_e) in bullet_q.iter() {
-                        if &bullet_e == a {
-                            hp.0 = hp.0.sub(dmg.0);
-                            iframes.0 = 0.2;
-                            cmd.entity(bullet_e).despawn();
-
-                            if ai.state != AggressiveAiState::KillMode {
-                                ai.state = AggressiveAiState::CheckLocation(o.0);
-                            }
-
-                            if hp.0 <= 0.0 {
-                                cmd.entity(entity).despawn();
-                                entity_death_event.send(SpawnedEntiyDeathEvent(sid.0));
-                                sound_event.send(SoundEvent::Death);
-                                xp_event.send(XpDropEvent(transform.translation, 10.));
-                            } else {
-                                sound_event.send(SoundEvent::Damage);
-                            }
-                        }
+            let (enemy, bullet) = if enemy_q.contains(*a) {
+                (*a, *b)
+            } else {
+                (*b, *a)
+            };
+
+            if !enemy_q.contains(enemy) {
+                continue;
+            }
+
+            let Ok((bt, dmg, o, mut vel, mut hits, pierce, ricochet, splash, split, on_hit)) =
+                bullet_q.get_mut(bullet)
+            else {
+                continue;
+            };
+
+            if hits.0.contains(&enemy) {
+                continue;
+            }
+            hits.0.push(enemy);
+
+            damage_event.send(DamageEvent {
+                target: enemy,
+                amount: dmg.0,
+                from: Some(o.0),
+            });
+
+            if let Some(on_hit) = on_hit {
+                effect_event.send(ApplyStatusEffectEvent {
+                    target: enemy,
+                    effect: on_hit.0.clone(),
+                });
+            }
+
+            let impact = bt.translation.truncate();
+
+            if let Some(splash) = splash {
+                for (other, _) in enemies.within(impact, splash.radius) {
+                    if other != enemy {
+                        damage_event.send(DamageEvent {
+                            target: other,
+                            amount: splash.damage,
+                            from: Some(impact),
+                        });
                     }
                 }
             }
+
+            if let Some(split) = split {
+                let speed = vel.linvel.length();
+                for i in 0..split.0 {
+                    let angle = std::f32::consts::TAU * i as f32 / split.0 as f32;
+                    let dir = Vec2::from_angle(angle).rotate(vel.linvel.normalize_or_zero());
+
+                    let shard = fire_bullet(
+                        &mut cmd,
+                        &mut pool,
+                        0.0,
+                        bt.translation,
+                        (impact + dir).extend(bt.translation.z),
+                        Damage(dmg.0 * 0.5),
+                        LifeTime(SPLIT_LIFETIME),
+                        assets.bullet_sprite.clone(),
+                        speed,
+                    );
+                    cmd.entity(shard).insert(HitTargets(vec![enemy]));
+                }
+                cmd.entity(bullet).remove::<Split>();
+            }
+
+            if let Some(mut pierce) = pierce.filter(|p| p.0 > 0) {
+                pierce.0 -= 1;
+                continue;
+            }
+
+            if let Some(mut ricochet) = ricochet.filter(|r| r.0 > 0) {
+                let next = enemies.nearest_where(impact, RICOCHET_RANGE, |e| !hits.0.contains(&e));
+
+                if let Some((_, next)) = next {
+                    ricochet.0 -= 1;
+                    vel.linvel = (next - impact).normalize_or_zero() * vel.linvel.length();
+                    continue;
+                }
+            }
+
+            release_bullet(&mut cmd, &mut pool, bullet);
+        }
+    }
+}
+
+fn handle_damage_event(
+    mut cmd: Commands,
+    mut target_q: Query<(
+        &Transform,
+        &mut Health,
+        Option<&mut IFrames>,
+        Option<&XpReward>,
+        Option<&mut AggressiveAi>,
+        Has<Enemy>,
+        Has<Player>,
+    )>,
+    mut damage_events: EventReader<DamageEvent>,
+    mut sound_event: EventWriter<SoundEvent>,
+    mut xp_event: EventWriter<XpDropEvent>,
+    mut killed_event: EventWriter<EnemyKilledEvent>,
+    mut item_event: EventWriter<ItemDropEvent>,
+) {
+    for ev in damage_events.read() {
+        let Ok((transform, mut hp, iframes, reward, ai, is_enemy, is_player)) =
+            target_q.get_mut(ev.target)
+        else {
+            continue;
+        };
+
+        // Already killed by an earlier event this frame.
+        if hp.0 <= 0.0 {
+            continue;
+        }
+
+        // Only the player gets actual invulnerability, for everything else iframes are just a flash.
+        if is_player && iframes.as_ref().is_some_and(|i| i.0 > 0.0) {
+            continue;
+        }
+
+        hp.0 = hp.0.sub(ev.amount);
+        if let Some(mut iframes) = iframes {
+            iframes.0 = 0.2;
+        }
+
+        if let (Some(mut ai), Some(from)) = (ai, ev.from) {
+            if ai.state != AggressiveAiState::KillMode {
+                ai.state = AggressiveAiState::CheckLocation(from);
+            }
+        }
+
+        if hp.0 <= 0.0 && is_enemy {
+            cmd.entity(ev.target).despawn();
+            sound_event.send(SoundEvent::Death);
+            killed_event.send(EnemyKilledEvent);
+            if let Some(reward) = reward {
+                xp_event.send(XpDropEvent(transform.translation, reward.0));
+            }
+
+            let position = transform.translation.truncate();
+            if chance_one_in(15.0) {
+                item_event.send(ItemDropEvent(ItemType::HealthPotion, 1, position));
+            } else if chance_one_in(25.0) {
+                item_event.send(ItemDropEvent(ItemType::DamageBoost, 1, position));
+            } else if chance_one_in(25.0) {
+                item_event.send(ItemDropEvent(ItemType::PickupBoost, 1, position));
+            }
+        } else {
+            sound_event.send(SoundEvent::Damage);
         }
     }
 }
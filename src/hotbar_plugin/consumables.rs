@@ -0,0 +1,150 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    components::{Health, Hunger, ItemType, Thirst},
+    pickup_plugin::MagnetConfig,
+    status_effect_plugin::{ApplyStatusEffectEvent, StatusEffect, StatusEffectKind},
+    survival_plugin::SurvivalConfig,
+};
+
+/// Use-effect of an item the player carries in the inventory.
+pub trait Consumable: Send + Sync + 'static {
+    /// Applies the effect to `player`, returning `false` keeps the item in the inventory.
+    fn consume(&self, player: Entity, world: &mut World) -> bool;
+}
+
+#[derive(Default, Resource)]
+pub struct Consumables(HashMap<ItemType, Box<dyn Consumable>>);
+
+impl Consumables {
+    pub fn get(&self, item: ItemType) -> Option<&dyn Consumable> {
+        self.0.get(&item).map(|c| c.as_ref())
+    }
+
+    pub fn contains(&self, item: ItemType) -> bool {
+        self.0.contains_key(&item)
+    }
+}
+
+pub trait RegisterConsumable {
+    fn register_consumable(&mut self, item: ItemType, consumable: impl Consumable) -> &mut Self;
+}
+
+impl RegisterConsumable for App {
+    fn register_consumable(&mut self, item: ItemType, consumable: impl Consumable) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Consumables::default)
+            .0
+            .insert(item, Box::new(consumable));
+        self
+    }
+}
+
+pub struct HealthPotion {
+    pub heal: f32,
+}
+
+impl Consumable for HealthPotion {
+    fn consume(&self, player: Entity, world: &mut World) -> bool {
+        match world.get_mut::<Health>(player) {
+            Some(mut hp) if hp.0 < hp.1 => {
+                hp.0 = (hp.0 + self.heal).min(hp.1);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct Berries;
+
+impl Consumable for Berries {
+    fn consume(&self, player: Entity, world: &mut World) -> bool {
+        let config = world.resource::<SurvivalConfig>();
+        let restore = config.berries_restore_hunger;
+        let regen = StatusEffect::regen(config.berries_regen_per_sec, config.berries_regen_secs);
+
+        match world.get_mut::<Hunger>(player) {
+            Some(mut hunger) if hunger.0 < hunger.1 => {
+                hunger.0 = (hunger.0 + restore).min(hunger.1);
+                world.send_event(ApplyStatusEffectEvent {
+                    target: player,
+                    effect: regen,
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct Water;
+
+impl Consumable for Water {
+    fn consume(&self, player: Entity, world: &mut World) -> bool {
+        let restore = world.resource::<SurvivalConfig>().water_restore_thirst;
+
+        match world.get_mut::<Thirst>(player) {
+            Some(mut thirst) if thirst.0 < thirst.1 => {
+                thirst.0 = (thirst.0 + restore).min(thirst.1);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+pub struct Magnet;
+
+impl Consumable for Magnet {
+    fn consume(&self, player: Entity, world: &mut World) -> bool {
+        let duration = world.resource::<MagnetConfig>().duration_secs;
+
+        world.send_event(ApplyStatusEffectEvent {
+            target: player,
+            effect: StatusEffect::new(StatusEffectKind::Magnetism, 0.0, duration),
+        });
+
+        true
+    }
+}
+
+pub struct DamageBoost {
+    pub multiplier: f32,
+    pub duration_secs: f32,
+}
+
+impl Consumable for DamageBoost {
+    fn consume(&self, player: Entity, world: &mut World) -> bool {
+        world.send_event(ApplyStatusEffectEvent {
+            target: player,
+            effect: StatusEffect::new(
+                StatusEffectKind::DamageBoost,
+                self.multiplier - 1.0,
+                self.duration_secs,
+            ),
+        });
+
+        true
+    }
+}
+
+pub struct PickupBoost {
+    pub multiplier: f32,
+    pub duration_secs: f32,
+}
+
+impl Consumable for PickupBoost {
+    fn consume(&self, player: Entity, world: &mut World) -> bool {
+        world.send_event(ApplyStatusEffectEvent {
+            target: player,
+            effect: StatusEffect::new(
+                StatusEffectKind::PickupRangeBoost,
+                self.multiplier - 1.0,
+                self.duration_secs,
+            ),
+        });
+
+        true
+    }
+}
@@ -0,0 +1,269 @@
+pub mod consumables;
+
+use bevy::prelude::*;
+
+use crate::{
+    asset_loader_plugin::AssetLoader,
+    components::{Inventory, ItemType, Player},
+    input_map_plugin::{ActionState, InputAction},
+    pickup_plugin::item_sprite,
+};
+use consumables::{
+    Berries, Consumables, DamageBoost, HealthPotion, Magnet, PickupBoost, RegisterConsumable, Water,
+};
+
+pub const HOTBAR_SLOTS: usize = 5;
+
+#[derive(Debug, Clone, Default, Component, Reflect)]
+pub struct Hotbar {
+    pub slots: [Option<ItemType>; HOTBAR_SLOTS],
+    pub selected: usize,
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiHotbarSlot(usize);
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiHotbarIcon(usize);
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiHotbarCount(usize);
+
+pub struct HotbarPlugin;
+
+impl Plugin for HotbarPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Consumables>();
+        app.register_consumable(ItemType::HealthPotion, HealthPotion { heal: 250.0 })
+            .register_consumable(ItemType::Berries, Berries)
+            .register_consumable(ItemType::Water, Water)
+            .register_consumable(ItemType::Magnet, Magnet)
+            .register_consumable(
+                ItemType::DamageBoost,
+                DamageBoost {
+                    multiplier: 2.0,
+                    duration_secs: 15.0,
+                },
+            )
+            .register_consumable(
+                ItemType::PickupBoost,
+                PickupBoost {
+                    multiplier: 3.0,
+                    duration_secs: 20.0,
+                },
+            );
+        app.register_type::<Hotbar>();
+        app.add_systems(Startup, spawn_hotbar_display);
+        app.add_systems(
+            Update,
+            (
+                assign_hotbar_slots,
+                select_hotbar_slot,
+                use_selected_item,
+                update_hotbar_display,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn assign_hotbar_slots(
+    mut player_q: Query<(&Inventory, &mut Hotbar), With<Player>>,
+    consumables: Res<Consumables>,
+) {
+    if let Ok((inventory, mut hotbar)) = player_q.get_single_mut() {
+        for slot in hotbar.slots.iter_mut() {
+            if slot.is_some_and(|item| inventory.count(item) == 0) {
+                *slot = None;
+            }
+        }
+
+        for (item, count) in inventory.iter() {
+            if count == 0 || !consumables.contains(item) || hotbar.slots.contains(&Some(item)) {
+                continue;
+            }
+
+            if let Some(slot) = hotbar.slots.iter_mut().find(|slot| slot.is_none()) {
+                *slot = Some(item);
+            }
+        }
+    }
+}
+
+fn select_hotbar_slot(mut player_q: Query<&mut Hotbar, With<Player>>, actions: Res<ActionState>) {
+    const SLOT_ACTIONS: [InputAction; HOTBAR_SLOTS] = [
+        InputAction::Hotbar1,
+        InputAction::Hotbar2,
+        InputAction::Hotbar3,
+        InputAction::Hotbar4,
+        InputAction::Hotbar5,
+    ];
+
+    if let Ok(mut hotbar) = player_q.get_single_mut() {
+        if let Some(slot) = SLOT_ACTIONS.iter().position(|a| actions.just_pressed(*a)) {
+            hotbar.selected = slot;
+        }
+        if actions.just_pressed(InputAction::HotbarNext) {
+            hotbar.selected = (hotbar.selected + 1) % HOTBAR_SLOTS;
+        }
+        if actions.just_pressed(InputAction::HotbarPrevious) {
+            hotbar.selected = (hotbar.selected + HOTBAR_SLOTS - 1) % HOTBAR_SLOTS;
+        }
+    }
+}
+
+fn use_selected_item(
+    mut cmd: Commands,
+    player_q: Query<(&Hotbar, Entity), With<Player>>,
+    actions: Res<ActionState>,
+) {
+    if !actions.just_pressed(InputAction::UseItem) {
+        return;
+    }
+
+    if let Ok((hotbar, player)) = player_q.get_single() {
+        if let Some(item) = hotbar.slots[hotbar.selected] {
+            cmd.add(move |world: &mut World| {
+                world.resource_scope(|world, consumables: Mut<Consumables>| {
+                    let Some(consumable) = consumables.get(item) else {
+                        return;
+                    };
+
+                    let has_item = world
+                        .get::<Inventory>(player)
+                        .is_some_and(|inventory| inventory.count(item) > 0);
+
+                    if has_item && consumable.consume(player, world) {
+                        if let Some(mut inventory) = world.get_mut::<Inventory>(player) {
+                            inventory.take(item, 1);
+                        }
+                    }
+                });
+            });
+        }
+    }
+}
+
+fn spawn_hotbar_display(mut cmd: Commands, assets: Res<AssetLoader>) {
+    cmd.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(16.),
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(8.),
+            ..default()
+        },
+        ..default()
+    })
+    .insert(Name::new("Hotbar UI"))
+    .with_children(|parent| {
+        for slot in 0..HOTBAR_SLOTS {
+            parent
+                .spawn((
+                    UiHotbarSlot(slot),
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Px(48.),
+                            height: Val::Px(48.),
+                            border: UiRect::all(Val::Px(2.)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.4)),
+                        border_color: BorderColor(Color::GRAY),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        UiHotbarIcon(slot),
+                        ImageBundle {
+                            style: Style {
+                                width: Val::Px(32.),
+                                height: Val::Px(32.),
+                                ..default()
+                            },
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                    ));
+                    parent.spawn((
+                        UiHotbarCount(slot),
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: assets.font.clone(),
+                                font_size: 16.0,
+                                color: Color::WHITE,
+                            },
+                        )
+                        .with_style(Style {
+                            position_type: PositionType::Absolute,
+                            right: Val::Px(2.),
+                            bottom: Val::Px(0.),
+                            ..default()
+                        }),
+                    ));
+                });
+        }
+    });
+}
+
+fn update_hotbar_display(
+    player_q: Query<(&Hotbar, &Inventory), With<Player>>,
+    mut slot_q: Query<(&mut BorderColor, &UiHotbarSlot)>,
+    mut icon_q: Query<(
+        &mut UiImage,
+        &mut BackgroundColor,
+        &mut Visibility,
+        &UiHotbarIcon,
+    )>,
+    mut count_q: Query<(&mut Text, &UiHotbarCount)>,
+    assets: Res<AssetLoader>,
+) {
+    let Ok((hotbar, inventory)) = player_q.get_single() else {
+        return;
+    };
+
+    for (mut border, slot) in slot_q.iter_mut() {
+        let color = if slot.0 == hotbar.selected {
+            Color::WHITE
+        } else {
+            Color::GRAY
+        };
+
+        if border.0 != color {
+            border.0 = color;
+        }
+    }
+
+    for (mut image, mut color, mut visibility, icon) in icon_q.iter_mut() {
+        match hotbar.slots[icon.0] {
+            Some(item) => {
+                let (texture, sprite) = item_sprite(item, &assets);
+                if image.texture != texture {
+                    image.texture = texture;
+                }
+                if color.0 != sprite.color {
+                    color.0 = sprite.color;
+                }
+                visibility.set_if_neq(Visibility::Inherited);
+            }
+            None => {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+        }
+    }
+
+    for (mut text, count) in count_q.iter_mut() {
+        let value = hotbar.slots[count.0]
+            .map(|item| inventory.count(item).to_string())
+            .unwrap_or_default();
+
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
@@ -0,0 +1,335 @@
+use bevy::{
+    input::InputSystem,
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{camera_plugin::MousePosition, config};
+
+const BINDINGS_FILE: &str = "input.ron";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+pub enum InputAction {
+    Fire,
+    Gather,
+    Build,
+    KillMode,
+    Plant,
+    Dash,
+    Sprint,
+    UseItem,
+    Hotbar1,
+    Hotbar2,
+    Hotbar3,
+    Hotbar4,
+    Hotbar5,
+    HotbarNext,
+    HotbarPrevious,
+    CycleTargeting,
+    Interact,
+    Upgrade,
+    NextTurretType,
+    BuildGenerator,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AxisBinding {
+    pub negative: Vec<Binding>,
+    pub positive: Vec<Binding>,
+    pub gamepad: GamepadAxisType,
+}
+
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    pub move_x: AxisBinding,
+    pub move_y: AxisBinding,
+    pub aim_x: GamepadAxisType,
+    pub aim_y: GamepadAxisType,
+    pub deadzone: f32,
+    pub actions: HashMap<InputAction, Vec<Binding>>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            move_x: AxisBinding {
+                negative: vec![Binding::Key(KeyCode::KeyA)],
+                positive: vec![Binding::Key(KeyCode::KeyD)],
+                gamepad: GamepadAxisType::LeftStickX,
+            },
+            move_y: AxisBinding {
+                negative: vec![Binding::Key(KeyCode::KeyS)],
+                positive: vec![Binding::Key(KeyCode::KeyW)],
+                gamepad: GamepadAxisType::LeftStickY,
+            },
+            aim_x: GamepadAxisType::RightStickX,
+            aim_y: GamepadAxisType::RightStickY,
+            deadzone: 0.2,
+            actions: HashMap::from([
+                (
+                    InputAction::Fire,
+                    vec![
+                        Binding::Mouse(MouseButton::Left),
+                        Binding::Gamepad(GamepadButtonType::RightTrigger2),
+                    ],
+                ),
+                (
+                    InputAction::Gather,
+                    vec![
+                        Binding::Mouse(MouseButton::Right),
+                        Binding::Gamepad(GamepadButtonType::RightTrigger),
+                    ],
+                ),
+                (
+                    InputAction::Build,
+                    vec![
+                        Binding::Key(KeyCode::Space),
+                        Binding::Gamepad(GamepadButtonType::North),
+                    ],
+                ),
+                (
+                    InputAction::KillMode,
+                    vec![
+                        Binding::Key(KeyCode::Backspace),
+                        Binding::Gamepad(GamepadButtonType::Select),
+                    ],
+                ),
+                (
+                    InputAction::Plant,
+                    vec![
+                        Binding::Key(KeyCode::KeyR),
+                        Binding::Gamepad(GamepadButtonType::West),
+                    ],
+                ),
+                (
+                    InputAction::Dash,
+                    vec![
+                        Binding::Key(KeyCode::KeyQ),
+                        Binding::Gamepad(GamepadButtonType::East),
+                    ],
+                ),
+                (
+                    InputAction::Sprint,
+                    vec![
+                        Binding::Key(KeyCode::ShiftLeft),
+                        Binding::Gamepad(GamepadButtonType::LeftThumb),
+                    ],
+                ),
+                (
+                    InputAction::UseItem,
+                    vec![
+                        Binding::Key(KeyCode::KeyF),
+                        Binding::Gamepad(GamepadButtonType::DPadUp),
+                    ],
+                ),
+                (InputAction::Hotbar1, vec![Binding::Key(KeyCode::Digit1)]),
+                (InputAction::Hotbar2, vec![Binding::Key(KeyCode::Digit2)]),
+                (InputAction::Hotbar3, vec![Binding::Key(KeyCode::Digit3)]),
+                (InputAction::Hotbar4, vec![Binding::Key(KeyCode::Digit4)]),
+                (InputAction::Hotbar5, vec![Binding::Key(KeyCode::Digit5)]),
+                (
+                    InputAction::HotbarNext,
+                    vec![Binding::Gamepad(GamepadButtonType::DPadRight)],
+                ),
+                (
+                    InputAction::HotbarPrevious,
+                    vec![Binding::Gamepad(GamepadButtonType::DPadLeft)],
+                ),
+                (
+                    InputAction::CycleTargeting,
+                    vec![
+                        Binding::Key(KeyCode::KeyT),
+                        Binding::Gamepad(GamepadButtonType::DPadDown),
+                    ],
+                ),
+                (
+                    InputAction::Interact,
+                    vec![
+                        Binding::Key(KeyCode::KeyE),
+                        Binding::Gamepad(GamepadButtonType::South),
+                    ],
+                ),
+                (
+                    InputAction::Upgrade,
+                    vec![
+                        Binding::Key(KeyCode::KeyU),
+                        Binding::Gamepad(GamepadButtonType::LeftTrigger),
+                    ],
+                ),
+                (
+                    InputAction::NextTurretType,
+                    vec![
+                        Binding::Key(KeyCode::Tab),
+                        Binding::Gamepad(GamepadButtonType::RightThumb),
+                    ],
+                ),
+                (
+                    InputAction::BuildGenerator,
+                    vec![
+                        Binding::Key(KeyCode::KeyG),
+                        Binding::Gamepad(GamepadButtonType::Start),
+                    ],
+                ),
+            ]),
+        }
+    }
+}
+
+impl InputBindings {
+    fn load() -> Self {
+        let mut bindings: Self = config::load_or_default(BINDINGS_FILE);
+
+        // Actions added after the file was written keep their default bindings.
+        for (action, default) in Self::default().actions {
+            bindings.actions.entry(action).or_insert(default);
+        }
+
+        bindings
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum AimSource {
+    #[default]
+    Mouse,
+    Gamepad(Vec2),
+}
+
+/// What the player wants to do this frame, regardless of the device used.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct ActionState {
+    pressed: HashSet<InputAction>,
+    just_pressed: HashSet<InputAction>,
+    pub movement: Vec2,
+    pub aim: AimSource,
+    mouse_world: Vec2,
+    last_cursor: Vec2,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: InputAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: InputAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    /// World point aimed at from `from`, the stick has no distance so `distance` is used instead.
+    pub fn aim_point(&self, from: Vec2, distance: f32) -> Vec2 {
+        match self.aim {
+            AimSource::Mouse => self.mouse_world,
+            AimSource::Gamepad(direction) => from + direction * distance,
+        }
+    }
+
+    /// Presses `action` for this frame, for players driven by code instead of a device.
+    pub fn press(&mut self, action: InputAction) {
+        self.pressed.insert(action);
+        self.just_pressed.insert(action);
+    }
+
+    /// Aims at a world point the way the mouse would.
+    pub fn aim_at(&mut self, point: Vec2) {
+        self.aim = AimSource::Mouse;
+        self.mouse_world = point;
+    }
+
+    /// Lets go of everything, scripted players call this at the start of every frame.
+    pub fn release_all(&mut self) {
+        self.pressed.clear();
+        self.just_pressed.clear();
+        self.movement = Vec2::ZERO;
+    }
+}
+
+pub struct InputMapPlugin;
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputBindings::load());
+        app.init_resource::<ActionState>();
+        app.register_type::<InputAction>();
+        app.add_systems(PreUpdate, update_action_state.after(InputSystem));
+    }
+}
+
+fn update_action_state(
+    bindings: Res<InputBindings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepads: Res<Gamepads>,
+    mouse: Res<MousePosition>,
+    mut state: ResMut<ActionState>,
+) {
+    let pressed = |binding: &Binding| match *binding {
+        Binding::Key(key) => keys.pressed(key),
+        Binding::Mouse(button) => mouse_buttons.pressed(button),
+        Binding::Gamepad(button) => gamepads
+            .iter()
+            .any(|pad| gamepad_buttons.pressed(GamepadButton::new(pad, button))),
+    };
+    let just_pressed = |binding: &Binding| match *binding {
+        Binding::Key(key) => keys.just_pressed(key),
+        Binding::Mouse(button) => mouse_buttons.just_pressed(button),
+        Binding::Gamepad(button) => gamepads
+            .iter()
+            .any(|pad| gamepad_buttons.just_pressed(GamepadButton::new(pad, button))),
+    };
+    let axis = |axis: GamepadAxisType| {
+        gamepads
+            .iter()
+            .filter_map(|pad| gamepad_axes.get(GamepadAxis::new(pad, axis)))
+            .find(|value| value.abs() > bindings.deadzone)
+            .unwrap_or_default()
+    };
+    let digital_axis = |binding: &AxisBinding| {
+        let negative = binding.negative.iter().any(pressed) as i8;
+        let positive = binding.positive.iter().any(pressed) as i8;
+        (positive - negative) as f32
+    };
+
+    let state = state.as_mut();
+
+    state.pressed.clear();
+    state.just_pressed.clear();
+    for (action, action_bindings) in bindings.actions.iter() {
+        if action_bindings.iter().any(pressed) {
+            state.pressed.insert(*action);
+        }
+        if action_bindings.iter().any(just_pressed) {
+            state.just_pressed.insert(*action);
+        }
+    }
+
+    let digital = Vec2::new(
+        digital_axis(&bindings.move_x),
+        digital_axis(&bindings.move_y),
+    );
+    state.movement = if digital != Vec2::ZERO {
+        digital.normalize_or_zero()
+    } else {
+        Vec2::new(axis(bindings.move_x.gamepad), axis(bindings.move_y.gamepad))
+            .clamp_length_max(1.0)
+    };
+
+    let stick = Vec2::new(axis(bindings.aim_x), axis(bindings.aim_y));
+    if stick != Vec2::ZERO {
+        state.aim = AimSource::Gamepad(stick.normalize());
+    } else if mouse.screen_position != state.last_cursor {
+        state.aim = AimSource::Mouse;
+    }
+    state.last_cursor = mouse.screen_position;
+    state.mouse_world = mouse.world_position;
+}
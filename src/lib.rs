@@ -0,0 +1,110 @@
+pub mod aggressive_ai_plugin;
+pub mod asset_loader_plugin;
+pub mod biome_plugin;
+pub mod bullet_plugin;
+pub mod camera_plugin;
+pub mod collision_layers;
+pub mod components;
+pub mod config;
+pub mod difficulty_plugin;
+pub mod events;
+pub mod generic_plugin;
+pub mod hotbar_plugin;
+pub mod input_map_plugin;
+pub mod perk_plugin;
+pub mod pickup_plugin;
+pub mod player_plugin;
+pub mod pool;
+pub mod run_stats_plugin;
+pub mod sound_plugin;
+pub mod spatial_index;
+pub mod spawner_plugin;
+pub mod status_effect_plugin;
+pub mod survival_plugin;
+pub mod tree_plugin;
+pub mod turret_plugin;
+pub mod utils;
+pub mod weapon;
+pub mod xp_plugin;
+
+use std::time::Duration;
+
+use bevy::{app::PluginGroupBuilder, prelude::*, time::TimeUpdateStrategy};
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    asset_loader_plugin::AssetLoader, biome_plugin::BiomeMap, bullet_plugin::ProjectileConfig,
+    input_map_plugin::ActionState, pickup_plugin::MagnetConfig, spawner_plugin::SpawnerConfig,
+    survival_plugin::SurvivalConfig,
+};
+
+/// Biome seed of headless apps, so their maps are the same on every run.
+pub const HEADLESS_BIOME_SEED: u32 = 0;
+
+/// Everything the simulation needs and nothing that needs a window, a GPU, audio or input
+/// devices, so it runs under `MinimalPlugins` as well as `DefaultPlugins`.
+///
+/// The `AssetLoader` and `ActionState` resources are left to the app, the game gets them from
+/// `AssetLoaderPlugin` and `InputMapPlugin`. Configs the app inserted before adding the group are
+/// kept, the rest are read from `config/`.
+pub struct GameplayPlugins;
+
+impl PluginGroup for GameplayPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(32.0))
+            .add(difficulty_plugin::DifficultyPlugin)
+            .add(biome_plugin::BiomePlugin)
+            .add(player_plugin::PlayerPlugin)
+            .add(survival_plugin::SurvivalPlugin)
+            .add(status_effect_plugin::StatusEffectPlugin)
+            .add(hotbar_plugin::HotbarPlugin)
+            .add(generic_plugin::GenericPlugin)
+            .add(xp_plugin::XpPlugin)
+            .add(perk_plugin::PerkPlugin)
+            .add(tree_plugin::TreePlugin)
+            .add(pickup_plugin::PickupPlugin)
+            .add(spawner_plugin::SpawnerPlugin)
+            .add(aggressive_ai_plugin::AggressiveAiPlugin)
+            .add(turret_plugin::TurretPlugin)
+            .add(bullet_plugin::BulletPlugin)
+            .add(run_stats_plugin::RunStatsPlugin)
+    }
+}
+
+/// The gameplay on `MinimalPlugins`, every `update` advances time and physics by exactly
+/// `1 / fps` seconds. Used by the headless simulation and the integration tests.
+///
+/// Assets are empty handles and nothing fills the `ActionState`, the caller drives it. Configs
+/// are the defaults instead of the files in `config/`, which are never read or written, and the
+/// biome map always uses `HEADLESS_BIOME_SEED`.
+pub fn headless_app(fps: u32) -> App {
+    let dt = 1.0 / fps as f32;
+
+    let mut app = App::new();
+    app.insert_resource(SurvivalConfig::default())
+        .insert_resource(SpawnerConfig::default())
+        .insert_resource(MagnetConfig::default())
+        .insert_resource(ProjectileConfig::default())
+        .insert_resource(BiomeMap::new(HEADLESS_BIOME_SEED))
+        .add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            GameplayPlugins,
+        ))
+        .init_resource::<AssetLoader>()
+        .init_resource::<ActionState>()
+        .insert_resource(RapierConfiguration {
+            gravity: Vec2::ZERO,
+            timestep_mode: TimestepMode::Fixed { dt, substeps: 1 },
+            ..default()
+        })
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            dt,
+        )));
+
+    app.finish();
+    app.cleanup();
+    app
+}
@@ -1,52 +1,24 @@
-use aggressive_ai_plugin::AggressiveAiPlugin;
 use bevy::prelude::*;
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_rapier2d::prelude::*;
 
-use asset_loader_plugin::AssetLoaderPlugin;
-use bullet_plugin::BulletPlugin;
-use camera_plugin::CameraPlugin;
-use generic_plugin::GenericPlugin;
-use pickup_plugin::PickupPlugin;
-use player_plugin::PlayerPlugin;
-use spawner_plugin::SpawnerPlugin;
-use tree_plugin::TreePlugin;
-use turret_plugin::TurretPlugin;
-use xp_plugin::XpPlugin;
-
-mod aggressive_ai_plugin;
-mod asset_loader_plugin;
-mod bullet_plugin;
-mod camera_plugin;
-mod components;
-mod events;
-mod generic_plugin;
-mod pickup_plugin;
-mod player_plugin;
-mod spawner_plugin;
-mod tree_plugin;
-mod turret_plugin;
-mod utils;
-mod weapon;
-mod xp_plugin;
+use survival::{
+    asset_loader_plugin::AssetLoaderPlugin, camera_plugin::CameraPlugin,
+    input_map_plugin::InputMapPlugin, run_stats_plugin::RunSummaryPlugin,
+    sound_plugin::SoundPlugin, GameplayPlugins,
+};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
         .add_plugins(WorldInspectorPlugin::new())
-        .add_plugins(RapierPhysicsPlugin::<NoUserData>::pixels_per_meter(32.0))
         .add_plugins(RapierDebugRenderPlugin::default())
         .add_plugins(AssetLoaderPlugin)
         .add_plugins(CameraPlugin)
-        .add_plugins(PlayerPlugin)
-        .add_plugins(GenericPlugin)
-        .add_plugins(XpPlugin)
-        .add_plugins(TreePlugin)
-        .add_plugins(PickupPlugin)
-        .add_plugins(SpawnerPlugin)
-        .add_plugins(AggressiveAiPlugin)
-        .add_plugins(TurretPlugin)
-        .add_plugins(BulletPlugin)
+        .add_plugins(InputMapPlugin)
+        .add_plugins(SoundPlugin)
+        .add_plugins(GameplayPlugins)
+        .add_plugins(RunSummaryPlugin)
         .insert_resource(ClearColor(Color::rgb_u8(33, 70, 33)))
         .insert_resource(RapierConfiguration {
             gravity: Vec2::ZERO,
@@ -0,0 +1,158 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    asset_loader_plugin::AssetLoader,
+    bullet_plugin::{ProjectileModifiers, SplashDamage},
+    components::Player,
+    xp_plugin::xp_level::XpLevel,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Perk {
+    Piercing,
+    Ricochet,
+    Homing,
+    Explosive,
+    Split,
+}
+
+impl Perk {
+    const ALL: [Perk; 5] = [
+        Perk::Piercing,
+        Perk::Ricochet,
+        Perk::Homing,
+        Perk::Explosive,
+        Perk::Split,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Piercing => "Piercing",
+            Self::Ricochet => "Ricochet",
+            Self::Homing => "Homing",
+            Self::Explosive => "Explosive",
+            Self::Split => "Split",
+        }
+    }
+
+    /// Perks stack, taking one again makes its modifier stronger.
+    fn apply(&self, modifiers: &mut ProjectileModifiers) {
+        match self {
+            Self::Piercing => modifiers.pierce += 1,
+            Self::Ricochet => modifiers.ricochet += 1,
+            Self::Homing => modifiers.homing = Some(modifiers.homing.unwrap_or(0.0) + 2.0),
+            Self::Explosive => {
+                modifiers.splash = Some(match modifiers.splash {
+                    Some(splash) => SplashDamage {
+                        radius: splash.radius + 16.0,
+                        damage: splash.damage + 1.0,
+                    },
+                    None => SplashDamage {
+                        radius: 48.0,
+                        damage: 1.0,
+                    },
+                })
+            }
+            Self::Split => modifiers.split += 2,
+        }
+    }
+}
+
+/// Perks the player picked up so far, one per level gained.
+#[derive(Debug, Clone, Default, Component, Reflect)]
+pub struct Perks {
+    pub taken: Vec<Perk>,
+    /// Highest level a perk has been granted for.
+    level: u32,
+}
+
+impl Perks {
+    pub fn at_level(level: u32) -> Self {
+        Self {
+            taken: vec![],
+            level,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiPerks;
+
+pub struct PerkPlugin;
+
+impl Plugin for PerkPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Perks>();
+        app.add_systems(Startup, spawn_perk_display);
+        app.add_systems(Update, (grant_level_up_perks, update_perk_display).chain());
+    }
+}
+
+fn grant_level_up_perks(
+    mut player_q: Query<
+        (&XpLevel, &mut Perks, &mut ProjectileModifiers),
+        (With<Player>, Changed<XpLevel>),
+    >,
+) {
+    let mut rng = rand::thread_rng();
+
+    for (lvl, mut perks, mut modifiers) in player_q.iter_mut() {
+        while perks.level < lvl.level {
+            let perk = Perk::ALL[rng.gen_range(0..Perk::ALL.len())];
+            perk.apply(&mut modifiers);
+            perks.taken.push(perk);
+            perks.level += 1;
+        }
+    }
+}
+
+fn spawn_perk_display(mut cmd: Commands, assets: Res<AssetLoader>) {
+    cmd.spawn((
+        UiPerks,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(96.),
+            left: Val::Px(16.),
+            ..default()
+        }),
+        Name::new("Perks UI"),
+    ));
+}
+
+fn update_perk_display(
+    player_q: Query<&Perks, (With<Player>, Changed<Perks>)>,
+    mut text_q: Query<&mut Text, With<UiPerks>>,
+) {
+    let Ok(perks) = player_q.get_single() else {
+        return;
+    };
+
+    let mut counts: Vec<(Perk, u32)> = vec![];
+    for perk in perks.taken.iter() {
+        match counts.iter_mut().find(|(p, _)| p == perk) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((*perk, 1)),
+        }
+    }
+
+    let lines: Vec<String> = counts
+        .iter()
+        .map(|(perk, count)| match count {
+            1 => perk.name().to_string(),
+            _ => format!("{} x{}", perk.name(), count),
+        })
+        .collect();
+
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = lines.join("\n");
+    }
+}
@@ -1,22 +1,68 @@
-use crate::xp_plugin::Xp;
-use bevy::prelude::*;
-use bevy_rapier2d::dynamics::Velocity;
+use std::{f32::consts::TAU, time::Duration};
+
+use bevy::{prelude::*, time::common_conditions::on_timer};
+use bevy_rapier2d::{
+    dynamics::{RigidBody, Velocity},
+    geometry::{Collider, Restitution},
+    pipeline::QueryFilter,
+    plugin::RapierContext,
+};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     asset_loader_plugin::AssetLoader,
-    components::{AttractedToPlayer, Magnet, PickupRange, PickupType, Player, PlayerPickup},
-    events::PickupTakenEvent,
+    components::{
+        Attractable, AttractedToPlayer, Inventory, ItemType, Magnet, PickupRange, PickupType,
+        Player, PlayerPickup, WaterSource,
+    },
+    config::InitConfig,
+    events::{PickupTakenEvent, SoundEvent},
+    pool::Pool,
+    spatial_index::{RegisterSpatialIndex, SpatialIndex},
+    status_effect_plugin::{Stat, StatusEffectExpiredEvent, StatusEffectKind, StatusEffects},
+    survival_plugin::SurvivalConfig,
+    utils::{random_in_range, random_vector},
+    xp_plugin::{release_xp, xp_level::XpLevel, Xp},
 };
 
+const MAGNET_CONFIG_FILE: &str = "magnet.ron";
+
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MagnetConfig {
+    /// Pickups closer than this to the player get pulled in while the magnet is active.
+    pub radius: f32,
+    pub duration_secs: f32,
+    pub attract_speed: f32,
+}
+
+impl Default for MagnetConfig {
+    fn default() -> Self {
+        Self {
+            radius: 2_000.0,
+            duration_secs: 10.0,
+            attract_speed: 1_000.0,
+        }
+    }
+}
+
 pub struct PickupPlugin;
 
 impl Plugin for PickupPlugin {
     fn build(&self, app: &mut App) {
+        app.init_config::<MagnetConfig>(MAGNET_CONFIG_FILE);
         app.add_event::<PickupTakenEvent>();
-        app.add_systems(Update, (spawn_magnets, take_player_pickups));
-        app.add_systems(Update, handle_pickup_taken);
+        app.init_spatial_index::<PlayerPickup>(128.0);
+        app.register_type::<Attractable>();
+        app.add_systems(Update, spawn_magnets);
+        app.add_systems(
+            Update,
+            spawn_water_sources.run_if(on_timer(Duration::from_secs(1))),
+        );
+        app.add_systems(Update, (take_player_pickups, handle_pickup_taken).chain());
         app.add_systems(Update, attract_pickups);
+        app.add_systems(Update, (magnetize_pickups, handle_magnet_expired));
     }
 }
 
@@ -34,12 +80,15 @@ fn spawn_magnets(
         let x = rng.gen_range(-SPAWN_RANGE..SPAWN_RANGE);
         let y = rng.gen_range(-SPAWN_RANGE..SPAWN_RANGE);
 
+        let (texture, sprite) = item_sprite(ItemType::Magnet, &assets);
+
         cmd.spawn((
             Magnet,
-            PlayerPickup(PickupType::Magnet),
+            PlayerPickup(PickupType::Item(ItemType::Magnet)),
             SpriteBundle {
                 transform: Transform::from_xyz(x, y, 0.0),
-                texture: assets.magnet_sprite.clone(),
+                texture,
+                sprite,
                 ..default()
             },
             Name::new("Magnet"),
@@ -47,16 +96,69 @@ fn spawn_magnets(
     }
 }
 
+fn spawn_water_sources(
+    mut cmd: Commands,
+    player_q: Query<&Transform, With<Player>>,
+    water_q: Query<(&Transform, Entity), With<WaterSource>>,
+    rapier: Res<RapierContext>,
+    config: Res<SurvivalConfig>,
+    assets: Res<AssetLoader>,
+) {
+    let Ok(player) = player_q.get_single() else {
+        return;
+    };
+    let player = player.translation.truncate();
+
+    let mut count = 0;
+    for (t, e) in water_q.iter() {
+        if t.translation.truncate().distance(player) > config.water_despawn_distance {
+            cmd.entity(e).despawn();
+        } else {
+            count += 1;
+        }
+    }
+
+    let mut rng = rand::thread_rng();
+    for _ in count..config.max_water_sources {
+        let distance =
+            rng.gen_range(config.water_spawn_min_distance..=config.water_spawn_max_distance);
+        let pos = player + Vec2::from_angle(rng.gen_range(0.0..TAU)) * distance;
+
+        // Skip spots taken by trees, buildings or anything else solid, the next check retries.
+        if rapier
+            .intersection_with_shape(pos, 0.0, &Collider::ball(16.0), QueryFilter::new())
+            .is_some()
+        {
+            continue;
+        }
+
+        let (texture, sprite) = item_sprite(ItemType::Water, &assets);
+
+        cmd.spawn((
+            WaterSource,
+            PlayerPickup(PickupType::Item(ItemType::Water)),
+            SpriteBundle {
+                transform: Transform::from_translation(pos.extend(0.0)),
+                texture,
+                sprite,
+                ..default()
+            },
+            Name::new("Water"),
+        ));
+    }
+}
+
 fn take_player_pickups(
-    player_q: Query<(&Transform, &PickupRange), With<Player>>,
-    pickup_q: Query<(&Transform, &PlayerPickup, Entity), Without<Player>>,
+    player_q: Query<(&Transform, &PickupRange, &StatusEffects), With<Player>>,
+    pickup_q: Query<&PlayerPickup, Without<Player>>,
+    pickups: Res<SpatialIndex<PlayerPickup>>,
     mut pickup_event: EventWriter<PickupTakenEvent>,
 ) {
-    let (p_transform, p_pickup_range) = player_q.single();
+    let (p_transform, p_pickup_range, effects) = player_q.single();
+    let range = p_pickup_range.0 * effects.multiplier(Stat::PickupRange);
 
-    for (t, pt, e) in pickup_q.iter() {
-        let dist = t.translation.distance(p_transform.translation);
-        if dist <= p_pickup_range.0 {
+    for (e, _) in pickups.within(p_transform.translation.truncate(), range) {
+        if let Ok(pt) = pickup_q.get(e) {
             pickup_event.send(PickupTakenEvent(e, pt.0));
         }
     }
@@ -64,41 +166,189 @@ fn take_player_pickups(
 
 fn handle_pickup_taken(
     mut cmd: Commands,
-    xp_q: Query<Entity, With<Xp>>,
+    mut player_q: Query<(&mut Inventory, &mut XpLevel), With<Player>>,
+    xp_q: Query<&Xp>,
     mut events: EventReader<PickupTakenEvent>,
+    mut sound_event: EventWriter<SoundEvent>,
+    mut xp_pool: ResMut<Pool<Xp>>,
 ) {
     for PickupTakenEvent(e, typ) in events.read() {
+        let Ok((mut inventory, mut lvl)) = player_q.get_single_mut() else {
+            continue;
+        };
+
         match typ {
-            PickupType::Magnet => {
-                for xp in xp_q.iter() {
-                    cmd.entity(xp).insert(AttractedToPlayer);
+            PickupType::Xp => {
+                if let Ok(xp) = xp_q.get(*e) {
+                    lvl.add_xp(*xp);
+                    sound_event.send(SoundEvent::XpPickup);
+                    release_xp(&mut cmd, &mut xp_pool, *e);
+                }
+            }
+            PickupType::Item(item) => {
+                inventory.add(*item, 1);
+                if let Some(mut e) = cmd.get_entity(*e) {
+                    e.despawn();
                 }
             }
-            PickupType::Item => {}
-        }
-        if let Some(mut e) = cmd.get_entity(*e) {
-            e.despawn();
         }
     }
     events.clear();
 }
 
+/// Pulls attractable pickups in once they are within twice the pickup range, or from anywhere while magnetized.
 fn attract_pickups(
-    player_q: Query<(&Transform, &PickupRange), With<Player>>,
-    mut xp_q: Query<(&mut Velocity, &Transform), (With<PlayerPickup>, Without<Player>)>,
-    time: Res<Time>,
+    player_q: Query<(&Transform, &PickupRange, &StatusEffects), With<Player>>,
+    mut pickup_q: Query<
+        (&mut Velocity, &Attractable),
+        (Without<AttractedToPlayer>, Without<Player>),
+    >,
+    mut magnetized_q: Query<
+        (&mut Velocity, &Transform),
+        (With<AttractedToPlayer>, Without<Player>),
+    >,
+    pickups: Res<SpatialIndex<PlayerPickup>>,
+    magnet: Res<MagnetConfig>,
 ) {
-    if let Ok((player, range)) = player_q.get_single() {
-        let attract_speed = 500.0;
-        let dt = time.delta_seconds();
-        for (mut v, t) in xp_q.iter_mut() {
-            let dist = player.translation.distance(t.translation);
-            if dist <= (range.0 * 2.0) {
-                let vector =
-                    ((player.translation - t.translation).normalize_or_zero() * dt * attract_speed)
-                        * dist;
-                v.linvel = vector.xy();
+    if let Ok((player, range, effects)) = player_q.get_single() {
+        let player_pos = player.translation.truncate();
+        let attract_range = range.0 * effects.multiplier(Stat::PickupRange) * 2.0;
+
+        for (e, pos) in pickups.within(player_pos, attract_range) {
+            if let Ok((mut v, attractable)) = pickup_q.get_mut(e) {
+                let offset = player_pos - pos;
+                let speed = attractable
+                    .speed_curve
+                    .speed(offset.length() / attract_range);
+
+                v.linvel = offset.normalize_or_zero() * speed;
             }
         }
+
+        for (mut v, t) in magnetized_q.iter_mut() {
+            let offset = player_pos - t.translation.truncate();
+            v.linvel = offset.normalize_or_zero() * magnet.attract_speed;
+        }
+    }
+}
+
+fn magnetize_pickups(
+    mut cmd: Commands,
+    player_q: Query<(&Transform, &StatusEffects), With<Player>>,
+    pickup_q: Query<(), (With<Attractable>, Without<AttractedToPlayer>)>,
+    pickups: Res<SpatialIndex<PlayerPickup>>,
+    config: Res<MagnetConfig>,
+) {
+    let Ok((player, effects)) = player_q.get_single() else {
+        return;
+    };
+
+    if !effects
+        .0
+        .iter()
+        .any(|e| e.kind == StatusEffectKind::Magnetism)
+    {
+        return;
+    }
+
+    for (e, _) in pickups.within(player.translation.truncate(), config.radius) {
+        if pickup_q.contains(e) {
+            cmd.entity(e).insert(AttractedToPlayer);
+        }
+    }
+}
+
+fn handle_magnet_expired(
+    mut cmd: Commands,
+    player_q: Query<Entity, With<Player>>,
+    attracted_q: Query<Entity, With<AttractedToPlayer>>,
+    mut events: EventReader<StatusEffectExpiredEvent>,
+) {
+    let Ok(player) = player_q.get_single() else {
+        return;
+    };
+
+    let expired = events
+        .read()
+        .any(|ev| ev.target == player && ev.kind == StatusEffectKind::Magnetism);
+
+    if expired {
+        for e in attracted_q.iter() {
+            cmd.entity(e).remove::<AttractedToPlayer>();
+        }
+    }
+}
+
+pub fn item_sprite(item: ItemType, assets: &AssetLoader) -> (Handle<Image>, Sprite) {
+    let (texture, size, color) = match item {
+        ItemType::Wood => (assets.item_wood_sprite.clone(), None, Color::WHITE),
+        ItemType::Sapling => (
+            assets.tree_main_sprite.clone(),
+            Some(Vec2::new(16., 32.)),
+            Color::WHITE,
+        ),
+        ItemType::Berries => (
+            assets.xp_sprite.clone(),
+            Some(Vec2::new(12., 12.)),
+            Color::rgb(0.8, 0.1, 0.3),
+        ),
+        ItemType::Water => (
+            assets.crystal_sprite.clone(),
+            Some(Vec2::new(16., 16.)),
+            Color::rgb(0.3, 0.6, 1.0),
+        ),
+        ItemType::Magnet => (assets.magnet_sprite.clone(), None, Color::WHITE),
+        ItemType::HealthPotion => (
+            assets.crystal_sprite.clone(),
+            Some(Vec2::new(16., 16.)),
+            Color::rgb(1.0, 0.2, 0.2),
+        ),
+        ItemType::DamageBoost => (
+            assets.crystal_sprite.clone(),
+            Some(Vec2::new(16., 16.)),
+            Color::rgb(1.0, 0.6, 0.1),
+        ),
+        ItemType::PickupBoost => (
+            assets.crystal_sprite.clone(),
+            Some(Vec2::new(16., 16.)),
+            Color::rgb(0.4, 1.0, 0.4),
+        ),
+    };
+
+    let sprite = Sprite {
+        custom_size: size,
+        color,
+        ..default()
+    };
+
+    (texture, sprite)
+}
+
+pub fn drop_item(
+    cmd: &mut Commands,
+    item: ItemType,
+    position: Vec2,
+    count: u32,
+    assets: &AssetLoader,
+) {
+    let (texture, sprite) = item_sprite(item, assets);
+
+    for _ in 0..count {
+        let vector = random_vector() * random_in_range(-10.0, 10.0);
+
+        cmd.spawn((
+            PlayerPickup(PickupType::Item(item)),
+            Attractable::default(),
+            RigidBody::Dynamic,
+            Restitution::coefficient(5.0),
+            Velocity::linear(vector.truncate()),
+            SpriteBundle {
+                transform: Transform::from_xyz(position.x, position.y - 50.0, 20.0),
+                texture: texture.clone(),
+                sprite: sprite.clone(),
+                ..default()
+            },
+            Name::new(format!("{:?}", item)),
+        ));
     }
 }
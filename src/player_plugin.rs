@@ -4,48 +4,85 @@ use bevy_rapier2d::prelude::*;
 use crate::{
     aggressive_ai_plugin::{AggressiveAi, AggressiveAiState},
     asset_loader_plugin::AssetLoader,
-    bullet_plugin::fire_bullet,
-    camera_plugin::MousePosition,
+    bullet_plugin::{BulletFiredEvent, ProjectileModifiers},
+    collision_layers,
     components::{
-        Damage, Enemy, Gathering, Health, IFrames, LifeTime, MainCamera, PickupRange, Player,
-        UiLevelDisplayBar, UiLevelDisplayNumber,
+        Damage, Enemy, Gathering, Health, Hunger, IFrames, Inventory, ItemType, LifeTime,
+        MainCamera, PickupRange, Player, Stamina, Thirst, UiLevelDisplayBar, UiLevelDisplayNumber,
     },
-    events::SoundEvent,
-    turret_plugin::SpawnTurretEvent,
+    events::{ActivityEvent, SoundEvent},
+    hotbar_plugin::Hotbar,
+    input_map_plugin::{ActionState, InputAction},
+    perk_plugin::Perks,
+    status_effect_plugin::{Stat, StatusEffects},
+    survival_plugin::SurvivalConfig,
+    turret_plugin::{build_spot, turret_types::TurretTypes, SelectedTurretType, SpawnTurretEvent},
     xp_plugin::xp_level::XpLevel,
 };
 
 #[derive(Debug, Resource)]
 pub struct PlayerAttackTimer(Timer);
 
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct Dash {
+    pub speed: f32,
+    pub stamina_cost: f32,
+    pub iframes: f32,
+    pub direction: Vec2,
+    pub duration: Timer,
+    pub cooldown: Timer,
+}
+
+impl Dash {
+    pub fn new(speed: f32, stamina_cost: f32, duration_secs: f32, cooldown_secs: f32) -> Self {
+        let mut duration = Timer::from_seconds(duration_secs, TimerMode::Once);
+        let mut cooldown = Timer::from_seconds(cooldown_secs, TimerMode::Once);
+        duration.tick(duration.duration());
+        cooldown.tick(cooldown.duration());
+
+        Self {
+            speed,
+            stamina_cost,
+            // Slightly longer than the dash itself so we don't get hit while stopping.
+            iframes: duration_secs + 0.1,
+            direction: Vec2::ZERO,
+            duration,
+            cooldown,
+        }
+    }
+
+    pub fn is_dashing(&self) -> bool {
+        !self.duration.finished()
+    }
+}
+
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, spawn_player);
         app.insert_resource(PlayerAttackTimer(Timer::from_seconds(0.5, TimerMode::Once)));
-        app.add_systems(Update, move_player);
+        app.register_type::<Dash>();
+        app.add_systems(Update, (dash, move_player).chain());
         app.add_systems(Update, shoot_bullets);
         app.add_systems(Update, kill_mode);
         app.add_systems(Update, build_turret);
     }
 }
 
-fn spawn_player(mut cmd: Commands, asset_loader: Res<AssetLoader>) {
+fn spawn_player(
+    mut cmd: Commands,
+    asset_loader: Res<AssetLoader>,
+    survival_config: Res<SurvivalConfig>,
+) {
     let texture = asset_loader.player_sprite.clone();
 
     cmd.spawn((
         Player,
         RigidBody::Dynamic,
-        Gathering {
-            damage: 20.0,
-            range: 64.0,
-            delay_frames: 0.0,
-        },
-        XpLevel::with_level(1),
-        PickupRange(32.),
-        Health(1000., 1000.),
-        IFrames::default(),
+        Collider::capsule_y(40., 12.),
+        collision_layers::player(),
+        LockedAxes::ROTATION_LOCKED,
         Velocity::linear(Vec2 { x: 0.0, y: 0.0 }),
         SpriteBundle {
             texture,
@@ -57,6 +94,26 @@ fn spawn_player(mut cmd: Commands, asset_loader: Res<AssetLoader>) {
             ..Default::default()
         },
         Name::new("Player"),
+    ))
+    .insert((
+        Gathering {
+            damage: 20.0,
+            range: 64.0,
+            delay_frames: 0.0,
+        },
+        XpLevel::with_level(1),
+        PickupRange(32.),
+        Health(1000., 1000.),
+        Stamina(survival_config.max_stamina, survival_config.max_stamina),
+        Hunger(survival_config.max_hunger, survival_config.max_hunger),
+        Thirst(survival_config.max_thirst, survival_config.max_thirst),
+        Dash::new(500., survival_config.dash_cost.stamina, 0.2, 1.0),
+        IFrames::default(),
+        Inventory::default(),
+        StatusEffects::default(),
+        Hotbar::default(),
+        ProjectileModifiers::default(),
+        Perks::at_level(1),
     ));
 
     cmd.spawn(NodeBundle {
@@ -115,59 +172,102 @@ fn spawn_player(mut cmd: Commands, asset_loader: Res<AssetLoader>) {
     });
 }
 
-fn move_player(
-    mut query: Query<(&mut Velocity, &mut Sprite), With<Player>>,
-    keys: Res<ButtonInput<KeyCode>>,
+fn dash(
+    mut query: Query<(&mut Dash, &mut Stamina, &mut IFrames, &Sprite), With<Player>>,
+    actions: Res<ActionState>,
+    time: Res<Time>,
+    mut activity_event: EventWriter<ActivityEvent>,
 ) {
-    let player_speed = 100.;
-    if let Ok((mut velocity, mut sprite)) = query.get_single_mut() {
-        let mut new_vel = Vec2::default();
+    if let Ok((mut dash, mut stamina, mut iframes, sprite)) = query.get_single_mut() {
+        dash.duration.tick(time.delta());
+        dash.cooldown.tick(time.delta());
 
-        if keys.pressed(KeyCode::KeyW) {
-            new_vel.y = 1.;
-        }
-        if keys.pressed(KeyCode::KeyS) {
-            new_vel.y = -1.;
-        }
+        if actions.just_pressed(InputAction::Dash)
+            && dash.cooldown.finished()
+            && stamina.0 >= dash.stamina_cost
+        {
+            // Standing still dashes the way the player is facing.
+            dash.direction = if actions.movement != Vec2::ZERO {
+                actions.movement.normalize()
+            } else if sprite.flip_x {
+                Vec2::NEG_X
+            } else {
+                Vec2::X
+            };
 
-        if keys.pressed(KeyCode::KeyA) {
-            new_vel.x = -1.;
+            stamina.0 -= dash.stamina_cost;
+            iframes.0 = iframes.0.max(dash.iframes);
+            dash.duration.reset();
+            dash.cooldown.reset();
+            activity_event.send(ActivityEvent::Dash);
         }
-        if keys.pressed(KeyCode::KeyD) {
-            new_vel.x = 1.;
+    }
+}
+
+fn move_player(
+    mut query: Query<
+        (
+            &mut Velocity,
+            &mut Sprite,
+            &Dash,
+            &Stamina,
+            &Thirst,
+            &StatusEffects,
+        ),
+        With<Player>,
+    >,
+    actions: Res<ActionState>,
+    survival_config: Res<SurvivalConfig>,
+) {
+    if let Ok((mut velocity, mut sprite, dash, stamina, thirst, effects)) = query.get_single_mut() {
+        if dash.is_dashing() {
+            velocity.linvel = dash.direction * dash.speed;
+            return;
         }
 
+        let player_speed =
+            100. * survival_config.speed_multiplier(
+                actions.pressed(InputAction::Sprint),
+                stamina,
+                thirst,
+            ) * effects.multiplier(Stat::MoveSpeed);
+
+        let new_vel = actions.movement;
+
         sprite.flip_x = new_vel.x < 0.0;
 
-        velocity.linvel = new_vel.normalize_or_zero() * player_speed;
+        velocity.linvel = new_vel * player_speed;
     }
 }
 
 fn shoot_bullets(
-    mut cmd: Commands,
-    player_q: Query<&Transform, (With<Player>, Without<MainCamera>)>,
+    player_q: Query<
+        (&Transform, &StatusEffects, &ProjectileModifiers),
+        (With<Player>, Without<MainCamera>),
+    >,
     mut attack_timer: ResMut<PlayerAttackTimer>,
     time: Res<Time>,
-    asset_loader: Res<AssetLoader>,
-    keys: Res<ButtonInput<MouseButton>>,
-    mouse: Res<MousePosition>,
+    actions: Res<ActionState>,
+    mut bullet_events: EventWriter<BulletFiredEvent>,
     mut sound_events: EventWriter<SoundEvent>,
 ) {
     attack_timer.0.tick(time.delta());
-    if let Ok(player) = player_q.get_single() {
-        if attack_timer.0.finished() && keys.pressed(MouseButton::Left) {
+    if let Ok((player, effects, modifiers)) = player_q.get_single() {
+        if attack_timer.0.finished() && actions.pressed(InputAction::Fire) {
             const BULLET_SPEED: f32 = 1_000.0;
+            const STICK_AIM_DISTANCE: f32 = 200.0;
+
+            let target = actions.aim_point(player.translation.truncate(), STICK_AIM_DISTANCE);
 
-            fire_bullet(
-                &mut cmd,
-                20.0,
-                player.translation,
-                mouse.world_position.extend(player.translation.z),
-                Damage(2.0),
-                LifeTime(120),
-                asset_loader.bullet_sprite.clone(),
-                BULLET_SPEED,
-            );
+            bullet_events.send(BulletFiredEvent {
+                from: player.translation.truncate(),
+                at: target,
+                acc: 20.0,
+                dmg: Damage(2.0 * effects.multiplier(Stat::Damage)),
+                lifetime: LifeTime(120),
+                bullet_speed: BULLET_SPEED,
+                modifiers: modifiers.clone(),
+            });
 
             sound_events.send(SoundEvent::PistolShoot);
             attack_timer.0.reset();
@@ -175,8 +275,8 @@ fn shoot_bullets(
     }
 }
 
-fn kill_mode(mut enemy_q: Query<&mut AggressiveAi, With<Enemy>>, keys: Res<ButtonInput<KeyCode>>) {
-    if keys.just_pressed(KeyCode::Backspace) {
+fn kill_mode(mut enemy_q: Query<&mut AggressiveAi, With<Enemy>>, actions: Res<ActionState>) {
+    if actions.just_pressed(InputAction::KillMode) {
         for mut e in enemy_q.iter_mut() {
             e.state = AggressiveAiState::KillMode;
         }
@@ -184,12 +284,20 @@ fn kill_mode(mut enemy_q: Query<&mut AggressiveAi, With<Enemy>>, keys: Res<Butto
 }
 
 fn build_turret(
-    player_q: Query<&Transform, With<Player>>,
-    keys: Res<ButtonInput<KeyCode>>,
+    mut player_q: Query<(&Transform, &mut Inventory), With<Player>>,
+    actions: Res<ActionState>,
+    selected: Res<SelectedTurretType>,
+    turret_types: Res<TurretTypes>,
     mut spawn_turret_ev: EventWriter<SpawnTurretEvent>,
 ) {
-    if keys.just_pressed(KeyCode::Space) {
-        let p = player_q.single();
-        spawn_turret_ev.send(SpawnTurretEvent(p.translation.truncate()));
+    if actions.just_pressed(InputAction::Build) {
+        let (p, mut inventory) = player_q.single_mut();
+        let Some(def) = turret_types.get(selected.0) else {
+            return;
+        };
+
+        if inventory.take(ItemType::Wood, def.cost) {
+            spawn_turret_ev.send(SpawnTurretEvent(build_spot(p, &actions), selected.0));
+        }
     }
 }
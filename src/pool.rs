@@ -0,0 +1,77 @@
+use std::marker::PhantomData;
+
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use bevy_rapier2d::prelude::*;
+
+/// Hands out entities tagged with `T` again instead of despawning and spawning new ones.
+///
+/// Released entities lose `T`, get hidden and have their physics disabled, but keep everything
+/// else. They only become available in `Last`, once the commands parking them have been applied.
+#[derive(Resource)]
+pub struct Pool<T: Component> {
+    free: Vec<Entity>,
+    released: Vec<Entity>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Component> Default for Pool<T> {
+    fn default() -> Self {
+        Self {
+            free: vec![],
+            released: vec![],
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Component> Pool<T> {
+    /// A parked entity with its physics and visibility back on, the caller inserts `T` again.
+    pub fn acquire<'a>(&mut self, cmd: &'a mut Commands) -> Option<EntityCommands<'a>> {
+        let entity = self.free.pop()?;
+
+        let mut entity = cmd.entity(entity);
+        entity
+            .remove::<(RigidBodyDisabled, ColliderDisabled)>()
+            .insert(Visibility::Inherited);
+        Some(entity)
+    }
+
+    /// Parks `entity`, returns `None` if it was already released this frame.
+    pub fn release<'a>(
+        &mut self,
+        cmd: &'a mut Commands,
+        entity: Entity,
+    ) -> Option<EntityCommands<'a>> {
+        if self.released.contains(&entity) {
+            return None;
+        }
+        self.released.push(entity);
+
+        let mut entity = cmd.entity(entity);
+        entity
+            .remove::<T>()
+            .insert((Visibility::Hidden, RigidBodyDisabled, ColliderDisabled));
+        Some(entity)
+    }
+
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+}
+
+pub trait RegisterPool {
+    fn init_pool<T: Component>(&mut self) -> &mut Self;
+}
+
+impl RegisterPool for App {
+    fn init_pool<T: Component>(&mut self) -> &mut Self {
+        self.init_resource::<Pool<T>>();
+        self.add_systems(Last, recycle_released::<T>);
+        self
+    }
+}
+
+fn recycle_released<T: Component>(mut pool: ResMut<Pool<T>>) {
+    let pool = pool.as_mut();
+    pool.free.append(&mut pool.released);
+}
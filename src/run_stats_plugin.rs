@@ -0,0 +1,310 @@
+use std::cmp::Reverse;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    asset_loader_plugin::AssetLoader,
+    components::{Health, Player},
+    config,
+    events::{EnemyKilledEvent, PickupTakenEvent, TreeDiedEvent},
+    turret_plugin::SpawnTurretEvent,
+    xp_plugin::{kill_streak::KillStreak, xp_level::XpLevel},
+};
+
+const HIGH_SCORES_FILE: &str = "highscores.ron";
+const MAX_HIGH_SCORES: usize = 10;
+
+const SCORE_PER_KILL: f32 = 10.0;
+const SCORE_PER_TREE: f32 = 25.0;
+const SCORE_PER_PICKUP: f32 = 1.0;
+const SCORE_PER_TURRET: f32 = 5.0;
+const SCORE_PER_SECOND: f32 = 1.0;
+
+#[derive(Debug, Clone, Resource)]
+pub struct RunStats {
+    pub score: f32,
+    /// Multiplies the score of every kill, grows with the kill streak.
+    pub combo: f32,
+    pub kills: u32,
+    pub trees_chopped: u32,
+    pub items_picked_up: u32,
+    pub turrets_built: u32,
+    pub xp_earned: f32,
+    pub survival_secs: f32,
+    pub finished: bool,
+}
+
+impl Default for RunStats {
+    fn default() -> Self {
+        Self {
+            score: 0.0,
+            combo: 1.0,
+            kills: 0,
+            trees_chopped: 0,
+            items_picked_up: 0,
+            turrets_built: 0,
+            xp_earned: 0.0,
+            survival_secs: 0.0,
+            finished: false,
+        }
+    }
+}
+
+impl RunStats {
+    fn combo_for_streak(kills: u32) -> f32 {
+        (1.0 + kills as f32 * 0.1).min(3.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub score: u32,
+    pub kills: u32,
+    pub trees_chopped: u32,
+    pub survival_secs: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HighScores(pub Vec<HighScore>);
+
+impl HighScores {
+    /// Inserts the run in score order and keeps the best `MAX_HIGH_SCORES`.
+    pub fn record(&mut self, stats: &RunStats) {
+        self.0.push(HighScore {
+            score: stats.score as u32,
+            kills: stats.kills,
+            trees_chopped: stats.trees_chopped,
+            survival_secs: stats.survival_secs,
+        });
+        self.0.sort_by_key(|entry| Reverse(entry.score));
+        self.0.truncate(MAX_HIGH_SCORES);
+    }
+}
+
+/// Sent once when the player dies and the run stats stop counting.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct RunEndedEvent;
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiRunStats;
+
+pub struct RunStatsPlugin;
+
+impl Plugin for RunStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>();
+        app.add_event::<RunEndedEvent>();
+        app.add_systems(Startup, spawn_run_stats_display);
+        app.add_systems(
+            Update,
+            (
+                track_run_time,
+                handle_run_stat_events,
+                end_run_on_player_death,
+                update_run_stats_display,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Records the high score and shows the summary when a run ends, left out of headless runs so
+/// simulated runs don't end up in `highscores.ron`.
+pub struct RunSummaryPlugin;
+
+impl Plugin for RunSummaryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, show_run_summary.after(end_run_on_player_death));
+    }
+}
+
+fn track_run_time(
+    player_q: Query<&XpLevel, With<Player>>,
+    mut stats: ResMut<RunStats>,
+    streak: Res<KillStreak>,
+    time: Res<Time>,
+) {
+    if stats.finished {
+        return;
+    }
+
+    let dt = time.delta_seconds();
+    stats.survival_secs += dt;
+    stats.score += SCORE_PER_SECOND * dt;
+    stats.combo = RunStats::combo_for_streak(streak.kills);
+
+    if let Ok(lvl) = player_q.get_single() {
+        stats.xp_earned = lvl.total;
+    }
+}
+
+fn handle_run_stat_events(
+    mut stats: ResMut<RunStats>,
+    mut kill_events: EventReader<EnemyKilledEvent>,
+    mut tree_events: EventReader<TreeDiedEvent>,
+    mut pickup_events: EventReader<PickupTakenEvent>,
+    mut turret_events: EventReader<SpawnTurretEvent>,
+) {
+    if stats.finished {
+        return;
+    }
+
+    for _ in kill_events.read() {
+        stats.kills += 1;
+        stats.score += SCORE_PER_KILL * stats.combo;
+    }
+    for _ in tree_events.read() {
+        stats.trees_chopped += 1;
+        stats.score += SCORE_PER_TREE;
+    }
+    for _ in pickup_events.read() {
+        stats.items_picked_up += 1;
+        stats.score += SCORE_PER_PICKUP;
+    }
+    for _ in turret_events.read() {
+        stats.turrets_built += 1;
+        stats.score += SCORE_PER_TURRET;
+    }
+}
+
+fn end_run_on_player_death(
+    player_q: Query<&Health, With<Player>>,
+    mut stats: ResMut<RunStats>,
+    mut time: ResMut<Time<Virtual>>,
+    mut run_ended: EventWriter<RunEndedEvent>,
+) {
+    if stats.finished {
+        return;
+    }
+
+    let Ok(hp) = player_q.get_single() else {
+        return;
+    };
+
+    if hp.0 > 0.0 {
+        return;
+    }
+
+    stats.finished = true;
+    time.pause();
+    run_ended.send(RunEndedEvent);
+}
+
+fn show_run_summary(
+    mut cmd: Commands,
+    mut run_ended: EventReader<RunEndedEvent>,
+    stats: Res<RunStats>,
+    assets: Res<AssetLoader>,
+) {
+    if run_ended.read().count() == 0 {
+        return;
+    }
+
+    let mut high_scores = config::load_or_default::<HighScores>(HIGH_SCORES_FILE);
+    high_scores.record(&stats);
+    config::save(HIGH_SCORES_FILE, &high_scores);
+
+    spawn_run_summary(&mut cmd, &stats, &high_scores, &assets);
+}
+
+fn format_time(secs: f32) -> String {
+    let secs = secs as u32;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+fn spawn_run_stats_display(mut cmd: Commands, assets: Res<AssetLoader>) {
+    cmd.spawn((
+        UiRunStats,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_text_justify(JustifyText::Right)
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(16.),
+            right: Val::Px(16.),
+            ..default()
+        }),
+        Name::new("Run Stats UI"),
+    ));
+}
+
+fn update_run_stats_display(mut text_q: Query<&mut Text, With<UiRunStats>>, stats: Res<RunStats>) {
+    if !stats.is_changed() {
+        return;
+    }
+
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = format!(
+            "Score {}  x{:.1}\nKills {}  Trees {}\n{}",
+            stats.score as u32,
+            stats.combo,
+            stats.kills,
+            stats.trees_chopped,
+            format_time(stats.survival_secs),
+        );
+    }
+}
+
+fn spawn_run_summary(
+    cmd: &mut Commands,
+    stats: &RunStats,
+    high_scores: &HighScores,
+    assets: &AssetLoader,
+) {
+    let mut summary = format!(
+        "Run over\n\nScore {}\nSurvived {}\nKills {}\nTrees chopped {}\nItems picked up {}\nTurrets built {}\nXP earned {}\n\nHigh scores\n",
+        stats.score as u32,
+        format_time(stats.survival_secs),
+        stats.kills,
+        stats.trees_chopped,
+        stats.items_picked_up,
+        stats.turrets_built,
+        stats.xp_earned as u32,
+    );
+
+    for (i, entry) in high_scores.0.iter().enumerate() {
+        summary += &format!(
+            "{}. {}  ({} kills, {} trees, {})\n",
+            i + 1,
+            entry.score,
+            entry.kills,
+            entry.trees_chopped,
+            format_time(entry.survival_secs),
+        );
+    }
+
+    cmd.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.),
+            height: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        background_color: BackgroundColor(Color::rgba(0.0, 0.0, 0.0, 0.7)),
+        z_index: ZIndex::Global(10),
+        ..default()
+    })
+    .insert(Name::new("Run Summary UI"))
+    .with_children(|parent| {
+        parent.spawn(
+            TextBundle::from_section(
+                summary,
+                TextStyle {
+                    font: assets.font.clone(),
+                    font_size: 24.0,
+                    color: Color::WHITE,
+                },
+            )
+            .with_text_justify(JustifyText::Center),
+        );
+    });
+}
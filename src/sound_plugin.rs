@@ -0,0 +1,52 @@
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{asset_loader_plugin::AssetLoader, events::SoundEvent};
+
+/// Plays the `SoundEvent`s sent by the gameplay, left out of headless runs.
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, play_sound_event);
+    }
+}
+
+fn play_sound_event(
+    mut cmd: Commands,
+    mut sound_event: EventReader<SoundEvent>,
+    asset_loader: Res<AssetLoader>,
+) {
+    for ev in sound_event.read() {
+        let (sound, volume) = match ev {
+            SoundEvent::Damage => (asset_loader.damage_sound.clone(), 0.1),
+            SoundEvent::Death => (asset_loader.death_sound.clone(), 0.005),
+            SoundEvent::XpPickup => (asset_loader.xp_pickup_sound.clone(), 0.3),
+            SoundEvent::AttackTree => (asset_loader.attack_tree_sound.clone(), 0.5),
+            SoundEvent::TreeHitGround => (asset_loader.tree_hit_ground_sound.clone(), 0.7),
+            SoundEvent::PistolShoot => (asset_loader.pistol_shoot_sound.clone(), 0.5),
+            SoundEvent::MachineGunShoot => (asset_loader.machine_gun_shoot_sound.clone(), 0.2),
+            SoundEvent::CannonShoot => (asset_loader.cannon_shoot_sound.clone(), 0.6),
+            SoundEvent::FlamethrowerShoot => (asset_loader.flamethrower_shoot_sound.clone(), 0.05),
+            SoundEvent::SlowingTowerShoot => (asset_loader.slowing_tower_shoot_sound.clone(), 0.2),
+        };
+        // PLACEHOLDER: the turret sounds are borrowed clips played at another pitch, drop this once
+        // they have assets of their own.
+        let speed = match ev {
+            SoundEvent::CannonShoot => 0.5,
+            SoundEvent::FlamethrowerShoot => 1.6,
+            SoundEvent::SlowingTowerShoot => 0.7,
+            _ => 1.0,
+        };
+
+        cmd.spawn(AudioBundle {
+            source: sound,
+            settings: PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                volume: Volume::new(volume),
+                speed,
+                ..Default::default()
+            },
+        });
+    }
+    sound_event.clear();
+}
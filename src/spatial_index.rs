@@ -0,0 +1,136 @@
+use std::marker::PhantomData;
+
+use bevy::{prelude::*, utils::HashMap};
+
+/// Uniform grid over the positions of every entity tagged with `T`, so proximity queries only
+/// look at the few cells around a point instead of every entity.
+///
+/// Kept up to date in `PreUpdate` from `GlobalTransform` changes, entities that lose `T` or get
+/// despawned are dropped from it.
+#[derive(Resource)]
+pub struct SpatialIndex<T: Component> {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<(Entity, Vec2)>>,
+    cell_of: HashMap<Entity, IVec2>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Component> SpatialIndex<T> {
+    /// `cell_size` works best around the radius most queries use.
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::default(),
+            cell_of: HashMap::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    fn cell(&self, pos: Vec2) -> IVec2 {
+        (pos / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Adds `entity` or moves it if it is already indexed.
+    pub fn insert(&mut self, entity: Entity, pos: Vec2) {
+        let cell = self.cell(pos);
+
+        match self.cell_of.insert(entity, cell) {
+            Some(old) if old == cell => {
+                if let Some(entry) = self
+                    .cells
+                    .get_mut(&cell)
+                    .and_then(|entries| entries.iter_mut().find(|(e, _)| *e == entity))
+                {
+                    entry.1 = pos;
+                }
+                return;
+            }
+            Some(old) => self.remove_from_cell(entity, old),
+            None => {}
+        }
+
+        self.cells.entry(cell).or_default().push((entity, pos));
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(cell) = self.cell_of.remove(&entity) {
+            self.remove_from_cell(entity, cell);
+        }
+    }
+
+    fn remove_from_cell(&mut self, entity: Entity, cell: IVec2) {
+        if let Some(entries) = self.cells.get_mut(&cell) {
+            entries.retain(|(e, _)| *e != entity);
+            if entries.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cell_of.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cell_of.is_empty()
+    }
+
+    /// Every indexed entity within `radius` of `center`, in no particular order.
+    pub fn within(&self, center: Vec2, radius: f32) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        let min = self.cell(center - Vec2::splat(radius));
+        let max = self.cell(center + Vec2::splat(radius));
+        let radius_squared = radius * radius;
+
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .filter(move |(_, pos)| pos.distance_squared(center) <= radius_squared)
+    }
+
+    /// The closest entity within `radius` of `center` that passes `filter`.
+    pub fn nearest_where(
+        &self,
+        center: Vec2,
+        radius: f32,
+        filter: impl Fn(Entity) -> bool,
+    ) -> Option<(Entity, Vec2)> {
+        self.within(center, radius)
+            .filter(|(e, _)| filter(*e))
+            .min_by(|(_, a), (_, b)| {
+                a.distance_squared(center)
+                    .total_cmp(&b.distance_squared(center))
+            })
+    }
+
+    pub fn nearest(&self, center: Vec2, radius: f32) -> Option<(Entity, Vec2)> {
+        self.nearest_where(center, radius, |_| true)
+    }
+}
+
+pub trait RegisterSpatialIndex {
+    fn init_spatial_index<T: Component>(&mut self, cell_size: f32) -> &mut Self;
+}
+
+impl RegisterSpatialIndex for App {
+    fn init_spatial_index<T: Component>(&mut self, cell_size: f32) -> &mut Self {
+        self.insert_resource(SpatialIndex::<T>::new(cell_size));
+        self.add_systems(PreUpdate, update_spatial_index::<T>);
+        self
+    }
+}
+
+fn update_spatial_index<T: Component>(
+    mut index: ResMut<SpatialIndex<T>>,
+    moved_q: Query<(Entity, &GlobalTransform), (With<T>, Or<(Changed<GlobalTransform>, Added<T>)>)>,
+    mut removed: RemovedComponents<T>,
+) {
+    for e in removed.read() {
+        index.remove(e);
+    }
+
+    for (e, gt) in moved_q.iter() {
+        index.insert(e, gt.translation().truncate());
+    }
+}
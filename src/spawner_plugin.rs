@@ -1,38 +1,111 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
 use bevy_rapier2d::prelude::*;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     aggressive_ai_plugin::AggressiveAi,
     asset_loader_plugin::AssetLoader,
-    components::{Enemy, Health, IFrames, Player},
+    biome_plugin::{Biome, BiomeMap},
+    collision_layers,
+    components::{Enemy, Health, IFrames, MeleeAttack, Player, XpReward},
+    config::InitConfig,
+    difficulty_plugin::Difficulty,
+    spatial_index::{RegisterSpatialIndex, SpatialIndex},
+    status_effect_plugin::StatusEffects,
+    tree_plugin::TreeTrunk,
+    turret_plugin::{generator::Generator, Turret},
     utils::random_vector,
 };
 
+const SPAWNER_CONFIG_FILE: &str = "spawners.ron";
+
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpawnerConfig {
+    /// Spawners at difficulty level 0.
+    pub base_count: usize,
+    /// Extra spawners for every difficulty level.
+    pub count_per_level: usize,
+    pub max_count: usize,
+    /// New spawners are placed between these distances from the player.
+    pub min_player_distance: f32,
+    pub max_player_distance: f32,
+    /// Spawners further than this from the player are removed and placed again closer by.
+    pub cull_distance: f32,
+    /// Minimum distance between two spawners.
+    pub min_spacing: f32,
+    /// No spawners this close to a turret or generator, the player's base.
+    pub base_radius: f32,
+    /// No spawners this close to a tree trunk.
+    pub tree_clearance: f32,
+    /// Biomes spawners may be placed in.
+    pub biomes: Vec<Biome>,
+    /// Random spots tried every frame, the next frame tries again if none of them fit.
+    pub placement_attempts: u32,
+}
+
+impl Default for SpawnerConfig {
+    fn default() -> Self {
+        Self {
+            base_count: 8,
+            count_per_level: 2,
+            max_count: 30,
+            min_player_distance: 1_000.0,
+            max_player_distance: 8_000.0,
+            cull_distance: 10_000.0,
+            min_spacing: 1_200.0,
+            base_radius: 800.0,
+            tree_clearance: 96.0,
+            biomes: vec![Biome::Meadow, Biome::Wasteland],
+            placement_attempts: 8,
+        }
+    }
+}
+
+impl SpawnerConfig {
+    /// How many spawners should be around the player at a difficulty level.
+    pub fn count_for_level(&self, level: u32) -> usize {
+        (self.base_count + self.count_per_level * level as usize).min(self.max_count)
+    }
+}
+
 #[derive(Debug, Component, Reflect)]
 pub struct Spawner;
 
+/// The spawner an enemy counts towards.
 #[derive(Debug, Component, Reflect)]
 pub struct SpawnerId(pub Entity);
 
-#[derive(Debug, Event)]
-pub struct SpawnedEntiyDeathEvent(pub Entity);
-
 #[derive(Debug, Component, Reflect)]
 pub struct SpawnerSpawnTimer {
     pub timer: Timer,
     pub spawn_limit: u32,
+    /// Entities with a `SpawnerId` pointing here, kept up to date by `track_spawner_ownership`.
     pub alive_now: u32,
 }
 
+/// Which spawner each entity with a `SpawnerId` counts towards, `RemovedComponents` only tells
+/// which entity lost its `SpawnerId` and not what it pointed at.
+#[derive(Debug, Default, Resource)]
+struct SpawnerOwnership(HashMap<Entity, Entity>);
+
 pub struct SpawnerPlugin;
 
 impl Plugin for SpawnerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<SpawnedEntiyDeathEvent>();
+        app.init_config::<SpawnerConfig>(SPAWNER_CONFIG_FILE);
+        app.init_resource::<SpawnerOwnership>();
+        app.init_spatial_index::<Enemy>(256.0);
         app.add_systems(
             Update,
-            (spawn_spawners, spawner_tick, handle_spawned_entity_death),
+            (
+                (cull_far_spawners, spawn_spawners).chain(),
+                (track_spawner_ownership, spawner_tick).chain(),
+            ),
         );
         app.register_type::<Spawner>();
         app.register_type::<SpawnerSpawnTimer>();
@@ -49,83 +122,171 @@ fn spawner_tick(
         timer.timer.tick(time.delta());
 
         if timer.timer.just_finished() && timer.alive_now < timer.spawn_limit {
-            cmd.spawn(Enemy)
-                .insert(RigidBody::Dynamic)
-                .insert(Collider::capsule_y(44., 12.))
-                .insert(ActiveEvents::COLLISION_EVENTS)
-                .insert(Sensor)
-                .insert(IFrames::default())
-                .insert(Velocity::default())
-                .insert(Health(10., 10.))
-                .insert(SpawnerId(e))
-                .insert(AggressiveAi::with_view_range(200.0))
-                .insert(SpriteBundle {
-                    transform: Transform::from_translation(t.translation),
-                    texture: assets.enemy_sprite.clone(),
-                    sprite: Sprite {
-                        custom_size: Some(Vec2::new(128., 128.)),
-                        ..default()
-                    },
-                    ..Default::default()
-                })
-                .insert(Name::new("Enemy"));
-
-            timer.alive_now += 1;
+            spawn_enemy(&mut cmd, t.translation, e, &assets);
         }
     }
 }
 
+/// Spawns an enemy belonging to `spawner`, it counts towards the spawner's limit from the next
+/// frame on.
+pub fn spawn_enemy(cmd: &mut Commands, pos: Vec3, spawner: Entity, assets: &AssetLoader) -> Entity {
+    cmd.spawn(Enemy)
+        .insert(RigidBody::Dynamic)
+        .insert(Collider::capsule_y(44., 12.))
+        .insert(collision_layers::enemy())
+        .insert(LockedAxes::ROTATION_LOCKED)
+        .insert(ActiveEvents::COLLISION_EVENTS)
+        .insert(IFrames::default())
+        .insert(Velocity::default())
+        .insert(Health(10., 10.))
+        .insert(XpReward(10.))
+        .insert(MeleeAttack::new(5., 48., 1.))
+        .insert(StatusEffects::default())
+        .insert(SpawnerId(spawner))
+        .insert(AggressiveAi::with_view_range(200.0))
+        .insert(SpriteBundle {
+            transform: Transform::from_translation(pos),
+            texture: assets.enemy_sprite.clone(),
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(128., 128.)),
+                ..default()
+            },
+            ..Default::default()
+        })
+        .insert(Name::new("Enemy"))
+        .id()
+}
+
 fn spawn_spawners(
     mut cmd: Commands,
     player_q: Query<&Transform, With<Player>>,
-    spawner_q: Query<Entity, (With<Spawner>, Without<Player>)>,
+    spawner_q: Query<&Transform, (With<Spawner>, Without<Player>)>,
+    building_q: Query<&Transform, (Or<(With<Turret>, With<Generator>)>, Without<Player>)>,
+    trunks: Res<SpatialIndex<TreeTrunk>>,
+    biomes: Res<BiomeMap>,
+    difficulty: Res<Difficulty>,
+    config: Res<SpawnerConfig>,
     assets: Res<AssetLoader>,
 ) {
-    const MAX_SPAWNERS: usize = 20;
+    if spawner_q.iter().len() >= config.count_for_level(difficulty.level) {
+        return;
+    }
 
-    let spawners_count = spawner_q.iter().len();
+    let Ok(player) = player_q.get_single() else {
+        return;
+    };
 
-    if spawners_count < MAX_SPAWNERS {
-        let mut rng = rand::thread_rng();
+    let fits = |pos: Vec2| {
+        config.biomes.contains(&biomes.biome_at(pos))
+            && clear_of(spawner_q.iter(), pos, config.min_spacing)
+            && clear_of(building_q.iter(), pos, config.base_radius)
+            && trunks.within(pos, config.tree_clearance).next().is_none()
+    };
 
-        let t = player_q.single().translation;
+    let mut rng = rand::thread_rng();
+    let center = player.translation.truncate();
 
-        let vector = random_vector() * rng.gen_range(1000.0..8000.0);
+    let Some(pos) = (0..config.placement_attempts)
+        .map(|_| {
+            let distance = rng.gen_range(config.min_player_distance..=config.max_player_distance);
+            center + random_vector().truncate() * distance
+        })
+        .find(|pos| fits(*pos))
+    else {
+        return;
+    };
 
-        cmd.spawn((
-            Spawner,
-            SpawnerSpawnTimer {
-                timer: Timer::from_seconds(10.0, TimerMode::Repeating),
-                spawn_limit: 16,
-                alive_now: 0,
-            },
-            SpriteBundle {
-                sprite: Sprite {
-                    custom_size: Some(Vec2::new(32.0, 32.0)),
-                    ..default()
-                },
-                texture: assets.spawner_sprite.clone(),
-                transform: Transform::from_translation(t + vector),
+    cmd.spawn((
+        Spawner,
+        SpawnerSpawnTimer {
+            timer: Timer::from_seconds(10.0, TimerMode::Repeating),
+            spawn_limit: 16,
+            alive_now: 0,
+        },
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(32.0, 32.0)),
                 ..default()
             },
-            Name::new("Spawner"),
-        ));
+            texture: assets.spawner_sprite.clone(),
+            transform: Transform::from_translation(pos.extend(player.translation.z)),
+            ..default()
+        },
+        Name::new("Spawner"),
+    ));
+}
+
+fn clear_of<'a>(mut others: impl Iterator<Item = &'a Transform>, pos: Vec2, distance: f32) -> bool {
+    others.all(|t| t.translation.truncate().distance(pos) >= distance)
+}
+
+/// Removes spawners the player left far behind, `spawn_spawners` places new ones closer by.
+/// Their enemies stay around without a spawner.
+fn cull_far_spawners(
+    mut cmd: Commands,
+    player_q: Query<&Transform, With<Player>>,
+    spawner_q: Query<(&Transform, Entity), (With<Spawner>, Without<Player>)>,
+    config: Res<SpawnerConfig>,
+) {
+    let Ok(player) = player_q.get_single() else {
+        return;
+    };
+
+    for (t, e) in spawner_q.iter() {
+        if t.translation.distance(player.translation) > config.cull_distance {
+            cmd.entity(e).despawn_recursive();
+        }
     }
 }
 
-fn handle_spawned_entity_death(
+/// Keeps `alive_now` equal to the number of entities pointing at each spawner, however they
+/// stop existing. Entities whose spawner is gone lose their `SpawnerId`.
+fn track_spawner_ownership(
     mut cmd: Commands,
-    mut spawner_q: Query<(&mut SpawnerSpawnTimer, Entity), With<Spawner>>,
-    mut events: EventReader<SpawnedEntiyDeathEvent>,
+    mut ownership: ResMut<SpawnerOwnership>,
+    mut spawner_q: Query<&mut SpawnerSpawnTimer, With<Spawner>>,
+    owned_q: Query<(Entity, &SpawnerId), Changed<SpawnerId>>,
+    mut lost_spawner_id: RemovedComponents<SpawnerId>,
+    mut removed_spawners: RemovedComponents<Spawner>,
 ) {
-    for SpawnedEntiyDeathEvent(e) in events.read() {
-        if let Some(e) = cmd.get_entity(*e) {
-            for (mut spawner, spawner_e) in spawner_q.iter_mut() {
-                if spawner_e == e.id() {
-                    spawner.alive_now = spawner.alive_now.saturating_sub(1);
-                }
-            }
+    for e in lost_spawner_id.read() {
+        if let Some(spawner) = ownership.0.remove(&e) {
+            release_slot(&mut spawner_q, spawner);
+        }
+    }
+
+    for (e, SpawnerId(spawner)) in owned_q.iter() {
+        match ownership.0.insert(e, *spawner) {
+            Some(previous) if previous == *spawner => continue,
+            Some(previous) => release_slot(&mut spawner_q, previous),
+            None => {}
         }
+
+        if let Ok(mut timer) = spawner_q.get_mut(*spawner) {
+            timer.alive_now += 1;
+        } else {
+            ownership.0.remove(&e);
+            cmd.entity(e).remove::<SpawnerId>();
+        }
+    }
+
+    let removed: HashSet<Entity> = removed_spawners.read().collect();
+    if !removed.is_empty() {
+        ownership.0.retain(|e, spawner| {
+            if !removed.contains(spawner) {
+                return true;
+            }
+
+            if let Some(mut orphan) = cmd.get_entity(*e) {
+                orphan.remove::<SpawnerId>();
+            }
+            false
+        });
+    }
+}
+
+fn release_slot(spawner_q: &mut Query<&mut SpawnerSpawnTimer, With<Spawner>>, spawner: Entity) {
+    if let Ok(mut timer) = spawner_q.get_mut(spawner) {
+        timer.alive_now = timer.alive_now.saturating_sub(1);
     }
-    events.clear();
 }
@@ -0,0 +1,312 @@
+use bevy::prelude::*;
+
+use crate::{
+    asset_loader_plugin::AssetLoader,
+    components::{Health, Player},
+    events::DamageEvent,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+pub enum StatusEffectKind {
+    Burn,
+    Regen,
+    Haste,
+    Slow,
+    DamageBoost,
+    XpBoost,
+    PickupRangeBoost,
+    Magnetism,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum Stat {
+    MoveSpeed,
+    Damage,
+    PickupRange,
+    XpGain,
+}
+
+impl StatusEffectKind {
+    pub fn modifies(&self) -> Option<Stat> {
+        match self {
+            Self::Haste | Self::Slow => Some(Stat::MoveSpeed),
+            Self::DamageBoost => Some(Stat::Damage),
+            Self::XpBoost => Some(Stat::XpGain),
+            Self::PickupRangeBoost => Some(Stat::PickupRange),
+            Self::Burn | Self::Regen | Self::Magnetism => None,
+        }
+    }
+}
+
+/// What happens when an effect of the same kind is applied while one is already active.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum Stacking {
+    /// Restart the duration.
+    Refresh,
+    /// Add the new duration to what is left.
+    Extend,
+    /// Add a stack and restart the duration.
+    Stack { max: u32 },
+    /// Keep the current effect untouched.
+    Ignore,
+}
+
+#[derive(Debug, Clone, Reflect)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    /// Damage or healing per tick for tick effects, fraction of the stat added per stack for modifiers.
+    pub magnitude: f32,
+    pub stacking: Stacking,
+    pub stacks: u32,
+    pub duration: Timer,
+    pub tick: Option<Timer>,
+}
+
+impl StatusEffect {
+    pub fn new(kind: StatusEffectKind, magnitude: f32, duration_secs: f32) -> Self {
+        Self {
+            kind,
+            magnitude,
+            stacking: Stacking::Refresh,
+            stacks: 1,
+            duration: Timer::from_seconds(duration_secs, TimerMode::Once),
+            tick: None,
+        }
+    }
+
+    pub fn ticking_every(mut self, secs: f32) -> Self {
+        self.tick = Some(Timer::from_seconds(secs, TimerMode::Repeating));
+        self
+    }
+
+    pub fn with_stacking(mut self, stacking: Stacking) -> Self {
+        self.stacking = stacking;
+        self
+    }
+
+    pub fn burn(damage_per_tick: f32, duration_secs: f32) -> Self {
+        Self::new(StatusEffectKind::Burn, damage_per_tick, duration_secs).ticking_every(0.5)
+    }
+
+    /// `amount` is the fraction of speed taken away, 0.5 halves it.
+    pub fn slow(amount: f32, duration_secs: f32) -> Self {
+        Self::new(StatusEffectKind::Slow, -amount, duration_secs)
+    }
+
+    pub fn regen(heal_per_sec: f32, duration_secs: f32) -> Self {
+        Self::new(StatusEffectKind::Regen, heal_per_sec, duration_secs)
+            .ticking_every(1.0)
+            .with_stacking(Stacking::Extend)
+    }
+}
+
+#[derive(Debug, Clone, Default, Component, Reflect)]
+pub struct StatusEffects(pub Vec<StatusEffect>);
+
+impl StatusEffects {
+    /// Returns `false` when the stacking rule rejected the effect.
+    pub fn apply(&mut self, effect: StatusEffect) -> bool {
+        let Some(active) = self.0.iter_mut().find(|e| e.kind == effect.kind) else {
+            self.0.push(effect);
+            return true;
+        };
+
+        match active.stacking {
+            Stacking::Refresh => {
+                active.magnitude = effect.magnitude;
+                active.duration = effect.duration;
+            }
+            Stacking::Extend => {
+                let left = active.duration.remaining();
+                active
+                    .duration
+                    .set_duration(left + effect.duration.duration());
+                active.duration.reset();
+            }
+            Stacking::Stack { max } => {
+                active.stacks = (active.stacks + 1).min(max);
+                active.duration = effect.duration;
+            }
+            Stacking::Ignore => return false,
+        }
+
+        true
+    }
+
+    /// Multiplier for `stat` from every active modifier, never below zero.
+    pub fn multiplier(&self, stat: Stat) -> f32 {
+        let bonus: f32 = self
+            .0
+            .iter()
+            .filter(|e| e.kind.modifies() == Some(stat))
+            .map(|e| e.magnitude * e.stacks as f32)
+            .sum();
+
+        (1.0 + bonus).max(0.0)
+    }
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct ApplyStatusEffectEvent {
+    pub target: Entity,
+    pub effect: StatusEffect,
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct StatusEffectAppliedEvent {
+    pub target: Entity,
+    pub kind: StatusEffectKind,
+}
+
+#[derive(Debug, Clone, Event)]
+pub struct StatusEffectExpiredEvent {
+    pub target: Entity,
+    pub kind: StatusEffectKind,
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiStatusEffects;
+
+pub struct StatusEffectPlugin;
+
+impl Plugin for StatusEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ApplyStatusEffectEvent>();
+        app.add_event::<StatusEffectAppliedEvent>();
+        app.add_event::<StatusEffectExpiredEvent>();
+        app.register_type::<StatusEffects>();
+        app.add_systems(Startup, spawn_status_display);
+        app.add_systems(
+            Update,
+            (
+                apply_status_effects,
+                tick_status_effects,
+                update_status_display,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn apply_status_effects(
+    mut target_q: Query<&mut StatusEffects>,
+    mut events: EventReader<ApplyStatusEffectEvent>,
+    mut applied_event: EventWriter<StatusEffectAppliedEvent>,
+) {
+    for ev in events.read() {
+        if let Ok(mut effects) = target_q.get_mut(ev.target) {
+            if effects.apply(ev.effect.clone()) {
+                applied_event.send(StatusEffectAppliedEvent {
+                    target: ev.target,
+                    kind: ev.effect.kind,
+                });
+            }
+        }
+    }
+}
+
+fn tick_status_effects(
+    mut target_q: Query<(&mut StatusEffects, Option<&mut Health>, Entity)>,
+    mut damage_event: EventWriter<DamageEvent>,
+    mut expired_event: EventWriter<StatusEffectExpiredEvent>,
+    time: Res<Time>,
+) {
+    for (mut effects, mut hp, e) in target_q.iter_mut() {
+        if effects.0.is_empty() {
+            continue;
+        }
+
+        for effect in effects.0.iter_mut() {
+            effect.duration.tick(time.delta());
+
+            let Some(tick) = effect.tick.as_mut() else {
+                continue;
+            };
+
+            let ticks = tick.tick(time.delta()).times_finished_this_tick() as f32;
+            let amount = effect.magnitude * effect.stacks as f32 * ticks;
+            if amount <= 0.0 {
+                continue;
+            }
+
+            match effect.kind {
+                StatusEffectKind::Burn => {
+                    damage_event.send(DamageEvent {
+                        target: e,
+                        amount,
+                        from: None,
+                    });
+                }
+                StatusEffectKind::Regen => {
+                    if let Some(hp) = hp.as_mut() {
+                        hp.0 = (hp.0 + amount).min(hp.1);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        effects.0.retain(|effect| {
+            let expired = effect.duration.finished();
+            if expired {
+                expired_event.send(StatusEffectExpiredEvent {
+                    target: e,
+                    kind: effect.kind,
+                });
+            }
+            !expired
+        });
+    }
+}
+
+fn spawn_status_display(mut cmd: Commands, assets: Res<AssetLoader>) {
+    cmd.spawn((
+        UiStatusEffects,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(72.),
+            left: Val::Px(16.),
+            ..default()
+        }),
+        Name::new("Status Effects UI"),
+    ));
+}
+
+fn update_status_display(
+    player_q: Query<(&StatusEffects, Entity), With<Player>>,
+    mut text_q: Query<&mut Text, With<UiStatusEffects>>,
+    mut applied_events: EventReader<StatusEffectAppliedEvent>,
+    mut expired_events: EventReader<StatusEffectExpiredEvent>,
+) {
+    let Ok((effects, player)) = player_q.get_single() else {
+        return;
+    };
+
+    let applied = applied_events.read().any(|ev| ev.target == player);
+    let expired = expired_events.read().any(|ev| ev.target == player);
+    if !applied && !expired {
+        return;
+    }
+
+    let value = effects
+        .0
+        .iter()
+        .map(|e| match e.stacks {
+            1 => format!("{:?}", e.kind),
+            n => format!("{:?} x{}", e.kind, n),
+        })
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    for mut text in text_q.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
@@ -0,0 +1,297 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{Health, Hunger, Player, Stamina, Thirst},
+    config::InitConfig,
+    events::ActivityEvent,
+    input_map_plugin::{ActionState, InputAction},
+    player_plugin::Dash,
+};
+
+const CONFIG_FILE: &str = "survival.ron";
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ActivityCost {
+    pub stamina: f32,
+    pub hunger: f32,
+    pub thirst: f32,
+}
+
+#[derive(Debug, Clone, Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SurvivalConfig {
+    pub max_stamina: f32,
+    pub max_hunger: f32,
+    pub max_thirst: f32,
+    pub stamina_regen_per_sec: f32,
+    pub hunger_drain_per_sec: f32,
+    pub thirst_drain_per_sec: f32,
+    pub chop_cost: ActivityCost,
+    pub dash_cost: ActivityCost,
+    pub sprint_cost_per_sec: ActivityCost,
+    pub sprint_speed_multiplier: f32,
+    /// Health lost every second while hunger is empty, stamina also stops regenerating.
+    pub starving_damage_per_sec: f32,
+    /// Health lost every second while thirst is empty, the player also moves slower.
+    pub dehydrated_damage_per_sec: f32,
+    pub dehydrated_speed_multiplier: f32,
+    /// Chopping takes this many times longer with no stamina left.
+    pub exhausted_gather_delay_multiplier: f32,
+    pub berries_restore_hunger: f32,
+    /// Berries also heal a little over time, eating more extends the effect.
+    pub berries_regen_per_sec: f32,
+    pub berries_regen_secs: f32,
+    pub water_restore_thirst: f32,
+    pub max_water_sources: usize,
+    /// Water sources appear between these distances from the player.
+    pub water_spawn_min_distance: f32,
+    pub water_spawn_max_distance: f32,
+    /// Water sources further than this from the player dry up to make room for closer ones.
+    pub water_despawn_distance: f32,
+}
+
+impl Default for SurvivalConfig {
+    fn default() -> Self {
+        Self {
+            max_stamina: 100.0,
+            max_hunger: 100.0,
+            max_thirst: 100.0,
+            stamina_regen_per_sec: 15.0,
+            hunger_drain_per_sec: 0.2,
+            thirst_drain_per_sec: 0.3,
+            chop_cost: ActivityCost {
+                stamina: 3.0,
+                hunger: 0.5,
+                thirst: 0.5,
+            },
+            dash_cost: ActivityCost {
+                stamina: 25.0,
+                hunger: 1.0,
+                thirst: 1.0,
+            },
+            sprint_cost_per_sec: ActivityCost {
+                stamina: 20.0,
+                hunger: 0.5,
+                thirst: 1.0,
+            },
+            sprint_speed_multiplier: 1.8,
+            starving_damage_per_sec: 5.0,
+            dehydrated_damage_per_sec: 5.0,
+            dehydrated_speed_multiplier: 0.6,
+            exhausted_gather_delay_multiplier: 3.0,
+            berries_restore_hunger: 20.0,
+            berries_regen_per_sec: 4.0,
+            berries_regen_secs: 10.0,
+            water_restore_thirst: 35.0,
+            max_water_sources: 40,
+            water_spawn_min_distance: 600.0,
+            water_spawn_max_distance: 5_000.0,
+            water_despawn_distance: 10_000.0,
+        }
+    }
+}
+
+impl SurvivalConfig {
+    /// Speed multiplier from sprinting and thirst, the caller decides whether sprint is held.
+    pub fn speed_multiplier(&self, sprinting: bool, stamina: &Stamina, thirst: &Thirst) -> f32 {
+        let mut multiplier = 1.0;
+
+        if sprinting && stamina.0 > 0.0 {
+            multiplier *= self.sprint_speed_multiplier;
+        }
+        if thirst.0 <= 0.0 {
+            multiplier *= self.dehydrated_speed_multiplier;
+        }
+
+        multiplier
+    }
+}
+
+#[derive(Debug, Clone, Copy, Component)]
+enum UiSurvivalBar {
+    Stamina,
+    Hunger,
+    Thirst,
+}
+
+pub struct SurvivalPlugin;
+
+impl Plugin for SurvivalPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_config::<SurvivalConfig>(CONFIG_FILE);
+        app.add_event::<ActivityEvent>();
+        app.register_type::<Hunger>();
+        app.register_type::<Thirst>();
+        app.add_systems(Startup, spawn_survival_display);
+        app.add_systems(
+            Update,
+            (
+                drain_survival_stats,
+                handle_activity_event,
+                apply_survival_penalties,
+                update_survival_display,
+            ),
+        );
+    }
+}
+
+fn apply_cost(
+    cost: &ActivityCost,
+    scale: f32,
+    stamina: &mut Stamina,
+    hunger: &mut Hunger,
+    thirst: &mut Thirst,
+) {
+    stamina.0 = (stamina.0 - cost.stamina * scale).max(0.0);
+    hunger.0 = (hunger.0 - cost.hunger * scale).max(0.0);
+    thirst.0 = (thirst.0 - cost.thirst * scale).max(0.0);
+}
+
+fn drain_survival_stats(
+    mut player_q: Query<(&mut Stamina, &mut Hunger, &mut Thirst, &Velocity, &Dash), With<Player>>,
+    actions: Res<ActionState>,
+    config: Res<SurvivalConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut stamina, mut hunger, mut thirst, velocity, dash) in player_q.iter_mut() {
+        hunger.0 = (hunger.0 - config.hunger_drain_per_sec * dt).max(0.0);
+        thirst.0 = (thirst.0 - config.thirst_drain_per_sec * dt).max(0.0);
+
+        let sprinting = actions.pressed(InputAction::Sprint)
+            && velocity.linvel != Vec2::ZERO
+            && stamina.0 > 0.0;
+
+        if sprinting {
+            apply_cost(
+                &config.sprint_cost_per_sec,
+                dt,
+                &mut stamina,
+                &mut hunger,
+                &mut thirst,
+            );
+        } else if !dash.is_dashing() && hunger.0 > 0.0 {
+            stamina.0 = (stamina.0 + config.stamina_regen_per_sec * dt).min(stamina.1);
+        }
+    }
+}
+
+fn handle_activity_event(
+    mut player_q: Query<(&mut Stamina, &mut Hunger, &mut Thirst), With<Player>>,
+    mut events: EventReader<ActivityEvent>,
+    config: Res<SurvivalConfig>,
+) {
+    for ev in events.read() {
+        if let Ok((mut stamina, mut hunger, mut thirst)) = player_q.get_single_mut() {
+            match ev {
+                ActivityEvent::Chop => apply_cost(
+                    &config.chop_cost,
+                    1.0,
+                    &mut stamina,
+                    &mut hunger,
+                    &mut thirst,
+                ),
+                // The dash pays its own stamina up front since it needs it to start.
+                ActivityEvent::Dash => apply_cost(
+                    &ActivityCost {
+                        stamina: 0.0,
+                        ..config.dash_cost
+                    },
+                    1.0,
+                    &mut stamina,
+                    &mut hunger,
+                    &mut thirst,
+                ),
+            }
+        }
+    }
+}
+
+fn apply_survival_penalties(
+    mut player_q: Query<(&mut Health, &Hunger, &Thirst), With<Player>>,
+    config: Res<SurvivalConfig>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (mut hp, hunger, thirst) in player_q.iter_mut() {
+        if hunger.0 <= 0.0 {
+            hp.0 -= config.starving_damage_per_sec * dt;
+        }
+        if thirst.0 <= 0.0 {
+            hp.0 -= config.dehydrated_damage_per_sec * dt;
+        }
+    }
+}
+
+fn spawn_survival_display(mut cmd: Commands) {
+    cmd.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(16.),
+            left: Val::Px(16.),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.),
+            ..default()
+        },
+        ..default()
+    })
+    .insert(Name::new("Survival UI"))
+    .with_children(|parent| {
+        for (bar, color) in [
+            (UiSurvivalBar::Stamina, Color::hex("E8C547").unwrap()),
+            (UiSurvivalBar::Hunger, Color::hex("C46A2B").unwrap()),
+            (UiSurvivalBar::Thirst, Color::hex("3D8FD1").unwrap()),
+        ] {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Px(160.0),
+                            height: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    Outline {
+                        color: Color::WHITE,
+                        offset: Val::Px(1.0),
+                        width: Val::Px(2.0),
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        bar,
+                        NodeBundle {
+                            style: Style {
+                                width: Val::Percent(100.0),
+                                ..default()
+                            },
+                            background_color: BackgroundColor(color),
+                            ..default()
+                        },
+                    ));
+                });
+        }
+    });
+}
+
+fn update_survival_display(
+    player_q: Query<(&Stamina, &Hunger, &Thirst), With<Player>>,
+    mut bar_q: Query<(&mut Style, &UiSurvivalBar)>,
+) {
+    if let Ok((stamina, hunger, thirst)) = player_q.get_single() {
+        for (mut style, bar) in bar_q.iter_mut() {
+            let (now, max) = match bar {
+                UiSurvivalBar::Stamina => (stamina.0, stamina.1),
+                UiSurvivalBar::Hunger => (hunger.0, hunger.1),
+                UiSurvivalBar::Thirst => (thirst.0, thirst.1),
+            };
+
+            style.width = Val::Percent(now / max * 100.0);
+        }
+    }
+}
@@ -1,20 +1,25 @@
 use crate::{
-    components::{PickupType, PlayerPickup},
-    xp_plugin::{drop_xp, Xp},
+    components::{Inventory, ItemType, XpReward},
+    pickup_plugin::drop_item,
 };
 use bevy::prelude::*;
 use bevy_rapier2d::{
-    dynamics::{RigidBody, Velocity},
-    geometry::Restitution,
+    dynamics::RigidBody, geometry::Collider, pipeline::QueryFilter, plugin::RapierContext,
 };
 use rand::Rng;
 
 use crate::{
     asset_loader_plugin::AssetLoader,
-    camera_plugin::{MouseHighlightedAction, MousePosition},
-    components::{Gathering, Health, IFrames, MainCamera, Player},
-    events::{ItemDropEvent, SoundEvent, TreeDiedEvent},
-    utils::{chance_one_in, random_in_range, random_vector},
+    bullet_plugin::{ProjectileBlockedEvent, ProjectileConfig},
+    camera_plugin::MouseHighlightedAction,
+    collision_layers,
+    components::{Enemy, Gathering, Health, IFrames, MainCamera, Player, Stamina},
+    events::{ActivityEvent, DamageEvent, ItemDropEvent, SoundEvent, TreeDiedEvent, XpDropEvent},
+    input_map_plugin::{ActionState, InputAction},
+    spatial_index::{RegisterSpatialIndex, SpatialIndex},
+    survival_plugin::SurvivalConfig,
+    turret_plugin::build_spot,
+    utils::{chance_one_in, random_in_range},
 };
 
 #[derive(Debug, Clone, Copy, Default, Component)]
@@ -23,23 +28,68 @@ pub struct TreeTrunk;
 #[derive(Debug, Clone, Copy, Component, Reflect)]
 pub enum TreeState {
     Standing,
-    Falling,
+    /// Horizontal direction the crown is falling towards, -1 is left and 1 is right.
+    Falling(f32),
     Dead,
 }
 
 #[derive(Debug, Clone, Copy, Default, Component)]
 pub struct Tree;
 
+/// Everything the falling crown already hit, so each target is only hurt once per fall.
+#[derive(Debug, Clone, Default, Component)]
+pub struct FallHits(Vec<Entity>);
+
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct Sapling;
+
+/// Lives on the `TreeTrunk`, a stump slowly grows back into a full tree.
+#[derive(Debug, Clone, Component, Reflect)]
+pub enum TreeGrowth {
+    Grown,
+    Stump(Timer),
+    Sapling(Timer),
+}
+
+impl TreeGrowth {
+    fn stump() -> Self {
+        Self::Stump(Timer::from_seconds(
+            random_in_range(60.0, 120.0),
+            TimerMode::Once,
+        ))
+    }
+
+    fn sapling() -> Self {
+        Self::Sapling(Timer::from_seconds(
+            random_in_range(60.0, 120.0),
+            TimerMode::Once,
+        ))
+    }
+}
+
 pub struct TreePlugin;
 
 impl Plugin for TreePlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<TreeDiedEvent>();
+        app.init_resource::<MouseHighlightedAction>();
+        app.init_spatial_index::<Tree>(128.0);
+        app.init_spatial_index::<TreeTrunk>(128.0);
         app.register_type::<TreeState>();
+        app.register_type::<TreeGrowth>();
         app.add_systems(Startup, spawn_trees);
         app.add_systems(
             Update,
-            (select_tree, cut_tree, update_trees, handle_tree_death),
+            (
+                select_tree,
+                cut_tree,
+                // A dead tree sends `TreeDiedEvent` every frame until it's despawned, so handle
+                // the first one right away.
+                (update_trees, handle_tree_death).chain(),
+                grow_trees,
+                plant_sapling,
+                shoot_trees,
+            ),
         );
     }
 }
@@ -51,27 +101,94 @@ fn update_trees(
             &GlobalTransform,
             &Health,
             &mut TreeState,
+            &mut FallHits,
+            &XpReward,
             Entity,
         ),
         With<Tree>,
     >,
+    player_q: Query<&Transform, (With<Player>, Without<Tree>)>,
+    damageable_q: Query<(), (With<Health>, Or<(With<Enemy>, With<Player>)>)>,
+    rapier: Res<RapierContext>,
     mut tree_died_event: EventWriter<TreeDiedEvent>,
+    mut damage_event: EventWriter<DamageEvent>,
 ) {
-    for (mut t, gt, hp, mut state, e) in tree_q.iter_mut() {
+    const FALL_SPEED: f32 = 0.04;
+    const FALL_DAMAGE: f32 = 50.0;
+    // Bottom of the crown sprite, relative to the trunk.
+    const PIVOT: Vec3 = Vec3::new(0.0, -12.0, 0.0);
+
+    for (mut t, gt, hp, mut state, mut hits, reward, e) in tree_q.iter_mut() {
         match *state {
             TreeState::Standing => {
                 if hp.0 <= 0.0 {
-                    *state = TreeState::Falling;
+                    let away_from_player = player_q
+                        .get_single()
+                        .map(|p| gt.translation().x - p.translation.x)
+                        .unwrap_or_default();
+
+                    *state = TreeState::Falling(if away_from_player < 0.0 { -1.0 } else { 1.0 });
                 }
             }
-            TreeState::Falling => {
-                t.rotate_z(0.04);
+            TreeState::Falling(direction) => {
+                // Trunks never rotate, so the crown's world position follows its local one.
+                let trunk = gt.translation() - t.translation;
+
+                t.rotate_around(PIVOT, Quat::from_rotation_z(-direction * FALL_SPEED));
                 if t.rotation.z.abs() > 0.7 {
                     *state = TreeState::Dead;
                 }
+
+                // Test the crown along the whole arc, not just where it lands.
+                let crown = (trunk + t.translation).truncate();
+                let (angle, _, _) = t.rotation.to_euler(EulerRot::ZYX);
+                rapier.intersections_with_shape(
+                    crown,
+                    angle,
+                    &Collider::cuboid(24.0, 64.0),
+                    QueryFilter::new(),
+                    |hit| {
+                        if damageable_q.contains(hit) && !hits.0.contains(&hit) {
+                            hits.0.push(hit);
+                            damage_event.send(DamageEvent {
+                                target: hit,
+                                amount: FALL_DAMAGE,
+                                from: Some(crown),
+                            });
+                        }
+                        true
+                    },
+                );
             }
             TreeState::Dead => {
-                tree_died_event.send(TreeDiedEvent(e, gt.translation(), 100.0));
+                tree_died_event.send(TreeDiedEvent(e, gt.translation(), reward.0));
+            }
+        }
+    }
+}
+
+/// Gunfire chips away at the tree directly, a `DamageEvent` would flash its iframes and block
+/// chopping for as long as a turret keeps firing at it.
+fn shoot_trees(
+    trunk_q: Query<&Children, With<TreeTrunk>>,
+    mut tree_q: Query<&mut Health, With<Tree>>,
+    mut blocked_events: EventReader<ProjectileBlockedEvent>,
+    config: Res<ProjectileConfig>,
+) {
+    for ev in blocked_events.read() {
+        if config.tree_damage_multiplier <= 0.0 {
+            continue;
+        }
+
+        let Ok(children) = trunk_q.get(ev.obstacle) else {
+            continue;
+        };
+
+        for tree in children.iter() {
+            if let Ok(mut hp) = tree_q.get_mut(*tree) {
+                if hp.0 > 0.0 {
+                    hp.0 -= ev.damage.0 * config.tree_damage_multiplier;
+                }
             }
         }
     }
@@ -84,37 +201,118 @@ fn spawn_trees(mut cmd: Commands, asset_loader: Res<AssetLoader>) {
         for y in -1000..1000 {
             if rng.gen_bool(1.0 / 1_000.0) {
                 let pos = Vec3::new((x * 10) as f32, (y * 10) as f32, 7.0);
+                spawn_tree_trunk(&mut cmd, pos, TreeGrowth::Grown, &asset_loader);
+            }
+        }
+    }
+}
 
-                cmd.spawn((
-                    TreeTrunk,
-                    SpriteBundle {
-                        sprite: Sprite {
-                            custom_size: Some(Vec2::new(64., 64.)),
-                            ..default()
-                        },
-                        transform: Transform::from_translation(pos),
-                        texture: asset_loader.tree_trunk_sprite.clone(),
-                        ..default()
-                    },
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Tree,
-                        Health(100.0, 100.0),
-                        TreeState::Standing,
-                        IFrames(0.0),
-                        SpriteBundle {
-                            sprite: Sprite {
-                                custom_size: Some(Vec2::new(64., 128.)),
-                                ..default()
-                            },
-                            transform: Transform::from_xyz(0.0, 52.0, 0.0),
-                            texture: asset_loader.tree_main_sprite.clone(),
-                            ..default()
-                        },
-                    ));
-                })
-                .insert(Name::new("Tree"));
+pub fn spawn_tree_trunk(
+    cmd: &mut Commands,
+    pos: Vec3,
+    growth: TreeGrowth,
+    asset_loader: &AssetLoader,
+) -> Entity {
+    let mut trunk = cmd.spawn((
+        TreeTrunk,
+        RigidBody::Fixed,
+        Collider::cuboid(12.0, 16.0),
+        collision_layers::world(),
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(64., 64.)),
+                ..default()
+            },
+            transform: Transform::from_translation(pos),
+            texture: asset_loader.tree_trunk_sprite.clone(),
+            ..default()
+        },
+        Name::new("Tree"),
+    ));
+
+    trunk.with_children(|parent| match growth {
+        TreeGrowth::Grown => spawn_tree(parent, asset_loader),
+        TreeGrowth::Sapling(_) => spawn_sapling(parent, asset_loader),
+        TreeGrowth::Stump(_) => {}
+    });
+
+    trunk.insert(growth).id()
+}
+
+fn spawn_tree(parent: &mut ChildBuilder, asset_loader: &AssetLoader) {
+    parent.spawn((
+        Tree,
+        FallHits::default(),
+        Health(100.0, 100.0),
+        XpReward(100.0),
+        TreeState::Standing,
+        IFrames(0.0),
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(64., 128.)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 52.0, 0.0),
+            texture: asset_loader.tree_main_sprite.clone(),
+            ..default()
+        },
+    ));
+}
+
+fn spawn_sapling(parent: &mut ChildBuilder, asset_loader: &AssetLoader) {
+    parent.spawn((
+        Sapling,
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(24., 48.)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 20.0, 0.0),
+            texture: asset_loader.tree_main_sprite.clone(),
+            ..default()
+        },
+    ));
+}
+
+fn grow_trees(
+    mut cmd: Commands,
+    mut trunk_q: Query<(&mut TreeGrowth, Entity), With<TreeTrunk>>,
+    time: Res<Time>,
+    asset_loader: Res<AssetLoader>,
+) {
+    for (mut growth, e) in trunk_q.iter_mut() {
+        match growth.as_mut() {
+            TreeGrowth::Grown => {}
+            TreeGrowth::Stump(timer) => {
+                if timer.tick(time.delta()).finished() {
+                    cmd.entity(e)
+                        .with_children(|parent| spawn_sapling(parent, &asset_loader));
+                    *growth = TreeGrowth::sapling();
+                }
+            }
+            TreeGrowth::Sapling(timer) => {
+                if timer.tick(time.delta()).finished() {
+                    cmd.entity(e)
+                        .despawn_descendants()
+                        .with_children(|parent| spawn_tree(parent, &asset_loader));
+                    *growth = TreeGrowth::Grown;
+                }
+            }
+        }
+    }
+}
+
+fn plant_sapling(
+    mut cmd: Commands,
+    mut player_q: Query<(&Transform, &mut Inventory), With<Player>>,
+    actions: Res<ActionState>,
+    asset_loader: Res<AssetLoader>,
+) {
+    if actions.just_pressed(InputAction::Plant) {
+        if let Ok((t, mut inventory)) = player_q.get_single_mut() {
+            if inventory.take(ItemType::Sapling, 1) {
+                let pos = build_spot(t, &actions).extend(7.0);
+                spawn_tree_trunk(&mut cmd, pos, TreeGrowth::sapling(), &asset_loader);
             }
         }
     }
@@ -122,25 +320,16 @@ fn spawn_trees(mut cmd: Commands, asset_loader: Res<AssetLoader>) {
 
 fn select_tree(
     player_q: Query<(&Transform, &Gathering), (With<Player>, Without<MainCamera>)>,
-    tree_q: Query<(&GlobalTransform, Entity), (With<Tree>, Without<Player>, Without<MainCamera>)>,
-    mouse: Res<MousePosition>,
+    trees: Res<SpatialIndex<Tree>>,
+    actions: Res<ActionState>,
     mut mouse_action: ResMut<MouseHighlightedAction>,
 ) {
     if let Ok((p_transform, p_range)) = player_q.get_single() {
-        let cursor_world = mouse.world_position;
-        let mut closest_dist = f32::MAX;
-        let mut closest_tree = None;
-
-        for (tree, e) in tree_q.iter() {
-            let dist = tree.translation().distance(cursor_world.extend(0.0));
-            if dist < closest_dist && dist < 32.0 {
-                closest_dist = dist;
-                closest_tree = Some((tree, e));
-            }
-        }
+        let cursor_world =
+            actions.aim_point(p_transform.translation.truncate(), p_range.range * 0.75);
 
-        if let Some((tree, e)) = closest_tree {
-            let dist_form_player = p_transform.translation.distance(tree.translation());
+        if let Some((e, tree)) = trees.nearest(cursor_world, 32.0) {
+            let dist_form_player = p_transform.translation.truncate().distance(tree);
             if dist_form_player <= p_range.range {
                 mouse_action.0 = Some(e);
             }
@@ -151,29 +340,45 @@ fn select_tree(
 }
 
 fn cut_tree(
-    mut player_q: Query<&mut Gathering, (With<Player>, Without<Tree>)>,
+    mut player_q: Query<(&mut Gathering, &Stamina), (With<Player>, Without<Tree>)>,
     mut tree_q: Query<(&mut GlobalTransform, &mut IFrames, &mut Health, Entity), With<Tree>>,
     mut mouse_action: ResMut<MouseHighlightedAction>,
-    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    actions: Res<ActionState>,
+    survival_config: Res<SurvivalConfig>,
     mut sound_event: EventWriter<SoundEvent>,
     mut drop_event: EventWriter<ItemDropEvent>,
+    mut activity_event: EventWriter<ActivityEvent>,
 ) {
-    if mouse_buttons.pressed(MouseButton::Right) && mouse_action.0.is_some() {
+    if actions.pressed(InputAction::Gather) && mouse_action.0.is_some() {
         for (t, mut iframes, mut hp, e) in tree_q.iter_mut() {
-            let mut player = player_q.single_mut();
+            let (mut player, stamina) = player_q.single_mut();
             if mouse_action.0.is_some()
                 && mouse_action.0.unwrap() == e
                 && iframes.0 <= 0.0
                 && player.delay_frames <= 0.0
             {
-                player.delay_frames = 0.30;
+                let delay = if stamina.0 <= 0.0 {
+                    0.30 * survival_config.exhausted_gather_delay_multiplier
+                } else {
+                    0.30
+                };
+
+                player.delay_frames = delay;
                 iframes.0 = 0.30;
                 hp.0 -= player.damage;
                 sound_event.send(SoundEvent::AttackTree);
+                activity_event.send(ActivityEvent::Chop);
                 mouse_action.0 = None;
 
                 if chance_one_in(10.0) {
-                    drop_event.send(ItemDropEvent::Wood(1, t.translation().truncate()));
+                    drop_event.send(ItemDropEvent(ItemType::Wood, 1, t.translation().truncate()));
+                }
+                if chance_one_in(8.0) {
+                    drop_event.send(ItemDropEvent(
+                        ItemType::Berries,
+                        1,
+                        t.translation().truncate(),
+                    ));
                 }
             }
         }
@@ -182,55 +387,44 @@ fn cut_tree(
 
 fn handle_tree_death(
     mut cmd: Commands,
+    tree_q: Query<&Parent, With<Tree>>,
     mut tree_death_ev: EventReader<TreeDiedEvent>,
+    mut xp_event: EventWriter<XpDropEvent>,
     mut sound_events: EventWriter<SoundEvent>,
     asset_loader: Res<AssetLoader>,
 ) {
     for TreeDiedEvent(e, pos, xp) in tree_death_ev.read() {
         let range = random_in_range(1.0, 5.0);
         for _i in 0..range as u32 {
-            let mut vector = random_vector();
-            vector *= random_in_range(-15.0, 15.0);
+            xp_event.send(XpDropEvent(*pos, *xp));
+        }
+
+        drop_item(
+            &mut cmd,
+            ItemType::Wood,
+            pos.truncate(),
+            random_in_range(1.0, 11.0) as u32,
+            &asset_loader,
+        );
 
-            drop_xp(
+        if chance_one_in(3.0) {
+            drop_item(
                 &mut cmd,
-                Xp(*xp),
+                ItemType::Sapling,
                 pos.truncate(),
-                vector.truncate(),
-                asset_loader.xp_sprite.clone(),
+                1,
+                &asset_loader,
             );
         }
 
-        drop_wood(
-            &mut cmd,
-            pos.truncate(),
-            random_in_range(0.0, 10.0) as u32,
-            asset_loader.item_wood_sprite.clone(),
-        );
+        if let Ok(trunk) = tree_q.get(*e) {
+            cmd.entity(trunk.get()).insert(TreeGrowth::stump());
+        }
 
-        if let Some(mut e) = cmd.get_entity(*e) {
-            e.despawn();
+        if let Some(e) = cmd.get_entity(*e) {
+            e.despawn_recursive();
         }
         sound_events.send(SoundEvent::TreeHitGround);
     }
     tree_death_ev.clear();
 }
-
-pub fn drop_wood(cmd: &mut Commands, position: Vec2, count: u32, texture: Handle<Image>) {
-    for _ in 0..=count {
-        let vector = random_vector() * random_in_range(-10.0, 10.0);
-
-        cmd.spawn((
-            PlayerPickup(PickupType::Item),
-            RigidBody::Dynamic,
-            Restitution::coefficient(5.0),
-            Velocity::linear(vector.truncate()),
-            SpriteBundle {
-                transform: Transform::from_xyz(position.x, position.y - 50.0, 20.0),
-                texture: texture.clone(),
-                ..default()
-            },
-            Name::new("Wood"),
-        ));
-    }
-}
@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use super::{build_spot, Turret, TurretAmmo};
+use crate::{
+    asset_loader_plugin::AssetLoader,
+    collision_layers,
+    components::{Inventory, ItemType, Player},
+    input_map_plugin::{ActionState, InputAction},
+};
+
+/// Wood needed to build a generator.
+pub const GENERATOR_COST: u32 = 20;
+const MAX_FUEL_SECS: f32 = 120.0;
+const FUEL_SECS_PER_WOOD: f32 = 10.0;
+const POWER_RADIUS: f32 = 400.0;
+/// Ammo every powered turret regains per second.
+const AMMO_PER_SEC: f32 = 5.0;
+
+/// Burns wood to keep the turrets around it supplied with ammo.
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct Generator {
+    pub fuel_secs: f32,
+}
+
+pub(super) fn build_generator(
+    mut cmd: Commands,
+    mut player_q: Query<(&Transform, &mut Inventory), With<Player>>,
+    actions: Res<ActionState>,
+    assets: Res<AssetLoader>,
+) {
+    if !actions.just_pressed(InputAction::BuildGenerator) {
+        return;
+    }
+
+    let Ok((player, mut inventory)) = player_q.get_single_mut() else {
+        return;
+    };
+
+    if !inventory.take(ItemType::Wood, GENERATOR_COST) {
+        return;
+    }
+
+    cmd.spawn((
+        Generator { fuel_secs: 0.0 },
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::splat(48.0)),
+                ..default()
+            },
+            texture: assets.crystal_sprite.clone(),
+            transform: Transform::from_translation(build_spot(player, &actions).extend(25.0)),
+            ..default()
+        },
+        RigidBody::Fixed,
+        Collider::cuboid(24.0, 24.0),
+        collision_layers::building(),
+        Name::new("Generator"),
+    ));
+}
+
+/// Fills `generator` up with as much of the player's wood as it takes.
+pub(super) fn fuel_generator(inventory: &mut Inventory, generator: &mut Generator) {
+    let missing = MAX_FUEL_SECS - generator.fuel_secs;
+    let wood = ((missing / FUEL_SECS_PER_WOOD).floor() as u32).min(inventory.count(ItemType::Wood));
+
+    if wood > 0 && inventory.take(ItemType::Wood, wood) {
+        generator.fuel_secs += wood as f32 * FUEL_SECS_PER_WOOD;
+    }
+}
+
+/// Refills turrets in range, fuel only burns while there is something to refill.
+pub(super) fn power_turrets(
+    mut generator_q: Query<(&Transform, &mut Generator)>,
+    mut turret_q: Query<(&Transform, &mut TurretAmmo), With<Turret>>,
+    time: Res<Time>,
+) {
+    let dt = time.delta_seconds();
+
+    for (gt, mut generator) in generator_q.iter_mut() {
+        if generator.fuel_secs <= 0.0 {
+            continue;
+        }
+
+        let mut powered_any = false;
+        for (tt, mut ammo) in turret_q.iter_mut() {
+            let in_range = tt
+                .translation
+                .truncate()
+                .distance(gt.translation.truncate())
+                <= POWER_RADIUS;
+
+            if in_range && ammo.missing() > 0.0 {
+                ammo.refill(AMMO_PER_SEC * dt);
+                powered_any = true;
+            }
+        }
+
+        if powered_any {
+            generator.fuel_secs = (generator.fuel_secs - dt).max(0.0);
+        }
+    }
+}
+
+pub(super) fn update_generator_display(
+    mut generator_q: Query<(&Generator, &mut Sprite), Changed<Generator>>,
+) {
+    for (generator, mut sprite) in generator_q.iter_mut() {
+        sprite.color = if generator.fuel_secs > 0.0 {
+            Color::rgb(1.0, 0.9, 0.3)
+        } else {
+            Color::GRAY
+        };
+    }
+}
@@ -0,0 +1,661 @@
+pub mod generator;
+pub mod turret_types;
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+use bevy_rapier2d::prelude::*;
+
+use crate::{
+    asset_loader_plugin::AssetLoader,
+    bullet_plugin::BulletFiredEvent,
+    collision_layers,
+    components::{Damage, Enemy, Health, Inventory, ItemType, LifeTime, MeleeAttack, Player},
+    events::{DamageEvent, SoundEvent},
+    input_map_plugin::{ActionState, InputAction},
+    spatial_index::SpatialIndex,
+    weapon::Weapon,
+};
+use generator::Generator;
+use turret_types::{RegisterTurretType, TurretType, TurretTypes};
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Turret;
+
+/// The rotating part of the turret, drawn as a child of the base.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct TurretHead;
+
+#[derive(Debug, Clone, Copy, Component)]
+pub struct TurretViewRange(pub f32);
+
+const MAX_TURRET_TIER: u32 = 3;
+/// How close the player has to be to a turret to repair, upgrade or retarget it.
+const INTERACT_REACH: f32 = 128.0;
+const REPAIR_HP_PER_WOOD: f32 = 25.0;
+const AMMO_PER_WOOD: f32 = 20.0;
+const TURRET_HALF_SIZE: f32 = 32.0;
+/// How far ahead of the player new buildings go, clear of the player's own collider.
+const BUILD_DISTANCE: f32 = 96.0;
+
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct TurretTier(pub u32);
+
+impl TurretTier {
+    /// Wood needed to reach the next tier.
+    pub fn upgrade_cost(&self) -> u32 {
+        self.0 * 10
+    }
+
+    fn tint(&self) -> Color {
+        match self.0 {
+            1 => Color::WHITE,
+            2 => Color::rgb(0.6, 0.8, 1.0),
+            _ => Color::rgb(1.0, 0.85, 0.3),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Component, Reflect)]
+pub enum TargetingMode {
+    #[default]
+    Nearest,
+    LowestHealth,
+    Strongest,
+    /// The enemy that has been in range the longest.
+    FirstIn,
+    /// Keeps shooting the same enemy until it dies or leaves range.
+    Locked,
+}
+
+impl TargetingMode {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Nearest => Self::LowestHealth,
+            Self::LowestHealth => Self::Strongest,
+            Self::Strongest => Self::FirstIn,
+            Self::FirstIn => Self::Locked,
+            Self::Locked => Self::Nearest,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Component)]
+pub struct TurretTarget {
+    pub entity: Option<Entity>,
+    /// Enemies in range, in the order they entered it.
+    in_range: Vec<Entity>,
+    /// Outside of locked mode the best target is re-picked this often, not every shot.
+    retarget: Timer,
+}
+
+impl Default for TurretTarget {
+    fn default() -> Self {
+        Self {
+            entity: None,
+            in_range: vec![],
+            retarget: Timer::from_seconds(0.5, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Shots left before the turret stops firing.
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+pub struct TurretAmmo {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl TurretAmmo {
+    pub fn full(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn missing(&self) -> f32 {
+        self.max - self.current
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current < 1.0
+    }
+
+    pub fn refill(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+}
+
+/// What the build action places next.
+#[derive(Debug, Clone, Copy, Default, Resource)]
+pub struct SelectedTurretType(pub TurretType);
+
+#[derive(Debug, Clone, Copy, Component)]
+struct UiSelectedTurret;
+
+#[derive(Debug, Clone, Event)]
+pub struct SpawnTurretEvent(pub Vec2, pub TurretType);
+
+pub struct TurretPlugin;
+
+impl Plugin for TurretPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpawnTurretEvent>();
+        app.init_resource::<SelectedTurretType>();
+        app.register_turret_type(TurretType::MachineGun, turret_types::machine_gun())
+            .register_turret_type(TurretType::Cannon, turret_types::cannon())
+            .register_turret_type(TurretType::Flamethrower, turret_types::flamethrower())
+            .register_turret_type(TurretType::SlowingTower, turret_types::slowing_tower());
+        app.register_type::<TargetingMode>();
+        app.register_type::<TurretTier>();
+        app.register_type::<TurretType>();
+        app.register_type::<TurretAmmo>();
+        app.register_type::<Generator>();
+        app.add_systems(Startup, spawn_selected_turret_display);
+        app.add_systems(
+            Update,
+            (
+                select_turret_type,
+                update_selected_turret_display,
+                handle_spawn_turret_event,
+            )
+                .chain(),
+        );
+        app.add_systems(
+            Update,
+            (
+                interact,
+                upgrade_turret,
+                enemies_attack_turrets,
+                destroy_turrets,
+                update_turret_tier_display,
+                update_turret_ammo_display,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                generator::build_generator,
+                generator::power_turrets,
+                generator::update_generator_display,
+            )
+                .chain(),
+        );
+        app.add_systems(
+            Update,
+            (
+                cycle_targeting_mode,
+                select_turret_target,
+                rotate_turret_heads,
+                turret_fire,
+            )
+                .chain(),
+        );
+    }
+}
+
+fn select_turret_type(mut selected: ResMut<SelectedTurretType>, actions: Res<ActionState>) {
+    if actions.just_pressed(InputAction::NextTurretType) {
+        selected.0 = selected.0.next();
+    }
+}
+
+fn spawn_selected_turret_display(mut cmd: Commands, assets: Res<AssetLoader>) {
+    cmd.spawn((
+        UiSelectedTurret,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.font.clone(),
+                font_size: 18.0,
+                color: Color::WHITE,
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(16.),
+            right: Val::Px(16.),
+            ..default()
+        }),
+        Name::new("Selected Turret UI"),
+    ));
+}
+
+fn update_selected_turret_display(
+    mut text_q: Query<&mut Text, With<UiSelectedTurret>>,
+    selected: Res<SelectedTurretType>,
+    turret_types: Res<TurretTypes>,
+) {
+    if !selected.is_changed() {
+        return;
+    }
+
+    if let Some(def) = turret_types.get(selected.0) {
+        for mut text in text_q.iter_mut() {
+            text.sections[0].value = format!("Build: {} ({} wood)", def.name, def.cost);
+        }
+    }
+}
+
+fn handle_spawn_turret_event(
+    mut cmd: Commands,
+    mut events: EventReader<SpawnTurretEvent>,
+    turret_types: Res<TurretTypes>,
+    asset_loader: Res<AssetLoader>,
+) {
+    for SpawnTurretEvent(pos, turret_type) in events.read() {
+        spawn_turret(
+            &mut cmd,
+            *pos,
+            *turret_type,
+            &turret_types,
+            asset_loader.turret_sprite.clone(),
+        );
+    }
+
+    events.clear();
+}
+
+pub fn spawn_turret(
+    cmd: &mut Commands,
+    pos: Vec2,
+    turret_type: TurretType,
+    turret_types: &TurretTypes,
+    texture: Handle<Image>,
+) {
+    let Some(def) = turret_types.get(turret_type) else {
+        return;
+    };
+
+    cmd.spawn((
+        Turret,
+        turret_type,
+        TurretViewRange(def.view_range),
+        TargetingMode::default(),
+        TurretTarget::default(),
+        TurretTier(1),
+        Health(def.health, def.health),
+        TurretAmmo::full(def.ammo_capacity),
+        Weapon::new(
+            Damage(def.damage),
+            def.bullet_speed,
+            def.accuracy,
+            def.fire_delay_secs,
+        )
+        .with_modifiers(def.modifiers.clone()),
+        (
+            RigidBody::Fixed,
+            Velocity::zero(),
+            Restitution::default(),
+            Collider::cuboid(32.0, 32.0),
+            collision_layers::building(),
+        ),
+        SpatialBundle::from_transform(Transform::from_translation(pos.extend(25.0))),
+        Name::new(def.name),
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            TurretHead,
+            SpriteBundle {
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(64.0)),
+                    ..default()
+                },
+                texture,
+                ..default()
+            },
+        ));
+    });
+}
+
+/// The closest of `entities` the player can interact with.
+pub(crate) fn closest_in_reach<'a>(
+    player: &Transform,
+    entities: impl Iterator<Item = (&'a Transform, Entity)>,
+) -> Option<Entity> {
+    entities
+        .map(|(t, e)| (t.translation.distance(player.translation), e))
+        .filter(|(dist, _)| *dist <= INTERACT_REACH)
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, e)| e)
+}
+
+/// Where the player places a new building, a short way ahead in the aim direction.
+pub(crate) fn build_spot(player: &Transform, actions: &ActionState) -> Vec2 {
+    let from = player.translation.truncate();
+    let direction = (actions.aim_point(from, BUILD_DISTANCE) - from)
+        .try_normalize()
+        .unwrap_or(Vec2::Y);
+
+    from + direction * BUILD_DISTANCE
+}
+
+fn cycle_targeting_mode(
+    player_q: Query<&Transform, With<Player>>,
+    mut turret_q: Query<(&Transform, &mut TargetingMode, &mut TurretTarget, Entity), With<Turret>>,
+    actions: Res<ActionState>,
+) {
+    if !actions.just_pressed(InputAction::CycleTargeting) {
+        return;
+    }
+
+    let Ok(player) = player_q.get_single() else {
+        return;
+    };
+
+    let closest = closest_in_reach(player, turret_q.iter().map(|(t, _, _, e)| (t, e)));
+
+    if let Some(Ok((_, mut mode, mut target, _))) = closest.map(|e| turret_q.get_mut(e)) {
+        *mode = mode.next();
+        target.entity = None;
+    }
+}
+
+/// Services whichever turret or generator is closest, so one press never spends wood on both.
+fn interact(
+    mut player_q: Query<(&Transform, &mut Inventory), With<Player>>,
+    mut turret_q: Query<
+        (&Transform, &mut Health, &mut TurretAmmo, Entity),
+        (With<Turret>, Without<Player>),
+    >,
+    mut generator_q: Query<(&Transform, &mut Generator, Entity), Without<Player>>,
+    actions: Res<ActionState>,
+) {
+    if !actions.just_pressed(InputAction::Interact) {
+        return;
+    }
+
+    let Ok((player, mut inventory)) = player_q.get_single_mut() else {
+        return;
+    };
+
+    let turrets = turret_q.iter().map(|(t, _, _, e)| (t, e));
+    let generators = generator_q.iter().map(|(t, _, e)| (t, e));
+    let Some(closest) = closest_in_reach(player, turrets.chain(generators)) else {
+        return;
+    };
+
+    if let Ok((_, mut hp, mut ammo, _)) = turret_q.get_mut(closest) {
+        service_turret(&mut inventory, &mut hp, &mut ammo);
+    } else if let Ok((_, mut generator, _)) = generator_q.get_mut(closest) {
+        generator::fuel_generator(&mut inventory, &mut generator);
+    }
+}
+
+/// Repairs the turret first, left over wood goes into ammo.
+fn service_turret(inventory: &mut Inventory, hp: &mut Health, ammo: &mut TurretAmmo) {
+    let missing = hp.1 - hp.0;
+    let wood = ((missing / REPAIR_HP_PER_WOOD).ceil() as u32).min(inventory.count(ItemType::Wood));
+
+    if wood > 0 && inventory.take(ItemType::Wood, wood) {
+        hp.0 = (hp.0 + wood as f32 * REPAIR_HP_PER_WOOD).min(hp.1);
+    }
+
+    let wood =
+        ((ammo.missing() / AMMO_PER_WOOD).ceil() as u32).min(inventory.count(ItemType::Wood));
+
+    if wood > 0 && inventory.take(ItemType::Wood, wood) {
+        ammo.refill(wood as f32 * AMMO_PER_WOOD);
+    }
+}
+
+fn upgrade_turret(
+    mut player_q: Query<(&Transform, &mut Inventory), With<Player>>,
+    mut turret_q: Query<
+        (
+            &Transform,
+            &mut TurretTier,
+            &mut TurretViewRange,
+            &mut Weapon,
+            &mut Health,
+            Entity,
+        ),
+        (With<Turret>, Without<Player>),
+    >,
+    actions: Res<ActionState>,
+) {
+    if !actions.just_pressed(InputAction::Upgrade) {
+        return;
+    }
+
+    let Ok((player, mut inventory)) = player_q.get_single_mut() else {
+        return;
+    };
+
+    let closest = closest_in_reach(player, turret_q.iter().map(|(t, _, _, _, _, e)| (t, e)));
+    let Some(Ok((_, mut tier, mut range, mut weapon, mut hp, _))) =
+        closest.map(|e| turret_q.get_mut(e))
+    else {
+        return;
+    };
+
+    if tier.0 >= MAX_TURRET_TIER || !inventory.take(ItemType::Wood, tier.upgrade_cost()) {
+        return;
+    }
+
+    tier.0 += 1;
+    range.0 *= 1.15;
+    weapon.damage.0 *= 1.5;
+    let delay = weapon.delay.duration().mul_f32(0.8);
+    weapon.delay.set_duration(delay);
+
+    let bonus_hp = hp.1 * 0.5;
+    hp.1 += bonus_hp;
+    hp.0 += bonus_hp;
+}
+
+fn enemies_attack_turrets(
+    mut enemy_q: Query<(&Transform, &mut MeleeAttack), (With<Enemy>, Without<Turret>)>,
+    turret_q: Query<(&Transform, Entity), With<Turret>>,
+    mut damage_event: EventWriter<DamageEvent>,
+    time: Res<Time>,
+) {
+    for (et, mut melee) in enemy_q.iter_mut() {
+        if !melee.cooldown.tick(time.delta()).finished() {
+            continue;
+        }
+
+        let reach = melee.range + TURRET_HALF_SIZE;
+        let target = turret_q
+            .iter()
+            .find(|(t, _)| t.translation.truncate().distance(et.translation.truncate()) <= reach);
+
+        if let Some((_, turret)) = target {
+            damage_event.send(DamageEvent {
+                target: turret,
+                amount: melee.damage,
+                from: Some(et.translation.truncate()),
+            });
+            melee.cooldown.reset();
+        }
+    }
+}
+
+fn destroy_turrets(
+    mut cmd: Commands,
+    turret_q: Query<(&Health, Entity), With<Turret>>,
+    mut sound_event: EventWriter<SoundEvent>,
+) {
+    for (hp, e) in turret_q.iter() {
+        if hp.0 <= 0.0 {
+            cmd.entity(e).despawn_recursive();
+            sound_event.send(SoundEvent::Death);
+        }
+    }
+}
+
+fn update_turret_tier_display(
+    turret_q: Query<(&TurretTier, &TurretType, &Children), Changed<TurretTier>>,
+    mut head_q: Query<(&mut Sprite, &mut Transform), With<TurretHead>>,
+    turret_types: Res<TurretTypes>,
+) {
+    for (tier, turret_type, children) in turret_q.iter() {
+        let type_tint = turret_types
+            .get(*turret_type)
+            .map_or(Color::WHITE, |def| def.tint);
+        let color = Color::rgba_from_array(type_tint.rgba_to_vec4() * tier.tint().rgba_to_vec4());
+
+        for child in children.iter() {
+            if let Ok((mut sprite, mut t)) = head_q.get_mut(*child) {
+                sprite.color = color.with_a(sprite.color.a());
+                t.scale = Vec3::splat(1.0 + (tier.0 - 1) as f32 * 0.1);
+            }
+        }
+    }
+}
+
+/// Fades the head of turrets that ran out of ammo.
+fn update_turret_ammo_display(
+    turret_q: Query<(&TurretAmmo, &Children), Changed<TurretAmmo>>,
+    mut head_q: Query<&mut Sprite, With<TurretHead>>,
+) {
+    for (ammo, children) in turret_q.iter() {
+        let alpha = if ammo.is_empty() { 0.4 } else { 1.0 };
+
+        for child in children.iter() {
+            if let Ok(mut sprite) = head_q.get_mut(*child) {
+                if sprite.color.a() != alpha {
+                    sprite.color.set_a(alpha);
+                }
+            }
+        }
+    }
+}
+
+fn select_turret_target(
+    enemy_q: Query<(&Transform, &Health, Entity), (With<Enemy>, Without<Turret>)>,
+    enemies: Res<SpatialIndex<Enemy>>,
+    mut turret_q: Query<
+        (
+            &Transform,
+            &TurretViewRange,
+            &TargetingMode,
+            &mut TurretTarget,
+        ),
+        With<Turret>,
+    >,
+    time: Res<Time>,
+) {
+    for (tt, vr, mode, mut target) in turret_q.iter_mut() {
+        let turret_pos = tt.translation.truncate();
+        let in_range = |e: Entity| {
+            enemy_q
+                .get(e)
+                .is_ok_and(|(t, _, _)| t.translation.truncate().distance(turret_pos) <= vr.0)
+        };
+
+        target.in_range.retain(|e| in_range(*e));
+        for (e, _) in enemies.within(turret_pos, vr.0) {
+            if in_range(e) && !target.in_range.contains(&e) {
+                target.in_range.push(e);
+            }
+        }
+
+        let retarget = target.retarget.tick(time.delta()).just_finished();
+        let current_valid = target.entity.is_some_and(|e| target.in_range.contains(&e));
+        if current_valid && (*mode == TargetingMode::Locked || !retarget) {
+            continue;
+        }
+
+        let candidates = target.in_range.iter().filter_map(|e| enemy_q.get(*e).ok());
+
+        let distance = |t: &Transform| t.translation.truncate().distance(turret_pos);
+
+        target.entity = match mode {
+            TargetingMode::Nearest | TargetingMode::Locked => candidates
+                .min_by(|(a, _, _), (b, _, _)| distance(a).total_cmp(&distance(b)))
+                .map(|(_, _, e)| e),
+            TargetingMode::LowestHealth => candidates
+                .min_by(|(_, a, _), (_, b, _)| a.0.total_cmp(&b.0))
+                .map(|(_, _, e)| e),
+            TargetingMode::Strongest => candidates
+                .max_by(|(_, a, _), (_, b, _)| a.1.total_cmp(&b.1).then(a.0.total_cmp(&b.0)))
+                .map(|(_, _, e)| e),
+            TargetingMode::FirstIn => target.in_range.first().copied(),
+        };
+    }
+}
+
+fn rotate_turret_heads(
+    enemy_q: Query<&GlobalTransform, With<Enemy>>,
+    turret_q: Query<(&GlobalTransform, &TurretTarget, &Children), With<Turret>>,
+    mut head_q: Query<&mut Transform, With<TurretHead>>,
+    time: Res<Time>,
+) {
+    const TURN_SPEED: f32 = 8.0;
+
+    for (gt, target, children) in turret_q.iter() {
+        let Some(enemy) = target.entity.and_then(|e| enemy_q.get(e).ok()) else {
+            continue;
+        };
+
+        let dir = (enemy.translation() - gt.translation()).truncate();
+        // The sprite points up, so straight up is zero rotation.
+        let wanted = Quat::from_rotation_z(dir.y.atan2(dir.x) - FRAC_PI_2);
+
+        for child in children.iter() {
+            if let Ok(mut head) = head_q.get_mut(*child) {
+                let step = (TURN_SPEED * time.delta_seconds()).min(1.0);
+                head.rotation = head.rotation.slerp(wanted, step);
+            }
+        }
+    }
+}
+
+fn turret_fire(
+    enemy_q: Query<&Transform, (With<Enemy>, Without<Turret>)>,
+    mut turret_q: Query<
+        (
+            &Transform,
+            &TurretTarget,
+            &TurretType,
+            &mut TurretAmmo,
+            &mut Weapon,
+        ),
+        With<Turret>,
+    >,
+    mut bullet_ev: EventWriter<BulletFiredEvent>,
+    mut sound_ev: EventWriter<SoundEvent>,
+    turret_types: Res<TurretTypes>,
+    dt: Res<Time>,
+) {
+    for (tt, target, turret_type, mut ammo, mut tw) in turret_q.iter_mut() {
+        let Some(def) = turret_types.get(*turret_type) else {
+            continue;
+        };
+
+        if ammo.is_empty() {
+            continue;
+        }
+
+        if let Some(target) = target.entity.and_then(|e| enemy_q.get(e).ok()) {
+            if tw.fire(&dt) {
+                ammo.current -= 1.0;
+
+                let from = tt.translation.truncate();
+                let aim = target.translation.truncate() - from;
+
+                for pellet in 0..def.pellets {
+                    // Pellets fan out evenly around the aim direction.
+                    let angle = if def.pellets > 1 {
+                        def.spread * (pellet as f32 / (def.pellets - 1) as f32 - 0.5)
+                    } else {
+                        0.0
+                    };
+
+                    bullet_ev.send(BulletFiredEvent {
+                        acc: tw.accuracy,
+                        at: from + Vec2::from_angle(angle).rotate(aim),
+                        from,
+                        dmg: tw.damage,
+                        lifetime: LifeTime(def.bullet_lifetime),
+                        bullet_speed: tw.bullet_velocity,
+                        modifiers: tw.modifiers.clone(),
+                    });
+                }
+
+                if let Some(sound) = def.sound {
+                    sound_ev.send(sound);
+                }
+            }
+        }
+    }
+}
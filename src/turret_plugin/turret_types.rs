@@ -0,0 +1,175 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    bullet_plugin::{ProjectileModifiers, SplashDamage},
+    events::SoundEvent,
+    status_effect_plugin::StatusEffect,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Component, Reflect)]
+pub enum TurretType {
+    #[default]
+    MachineGun,
+    Cannon,
+    Flamethrower,
+    SlowingTower,
+}
+
+impl TurretType {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::MachineGun => Self::Cannon,
+            Self::Cannon => Self::Flamethrower,
+            Self::Flamethrower => Self::SlowingTower,
+            Self::SlowingTower => Self::MachineGun,
+        }
+    }
+}
+
+/// Base stats of a turret type, upgrades scale them per turret.
+#[derive(Debug, Clone)]
+pub struct TurretDefinition {
+    pub name: &'static str,
+    /// Wood needed to build one.
+    pub cost: u32,
+    pub health: f32,
+    pub view_range: f32,
+    pub damage: f32,
+    pub bullet_speed: f32,
+    pub accuracy: f32,
+    pub fire_delay_secs: f32,
+    /// Shots a full turret holds.
+    pub ammo_capacity: f32,
+    /// In frames, like every other `LifeTime`.
+    pub bullet_lifetime: u32,
+    /// Projectiles per shot, fanned out evenly over `spread` radians.
+    pub pellets: u32,
+    pub spread: f32,
+    pub modifiers: ProjectileModifiers,
+    pub sound: Option<SoundEvent>,
+    pub tint: Color,
+}
+
+#[derive(Default, Resource)]
+pub struct TurretTypes(HashMap<TurretType, TurretDefinition>);
+
+impl TurretTypes {
+    pub fn get(&self, turret_type: TurretType) -> Option<&TurretDefinition> {
+        self.0.get(&turret_type)
+    }
+}
+
+pub trait RegisterTurretType {
+    fn register_turret_type(
+        &mut self,
+        turret_type: TurretType,
+        definition: TurretDefinition,
+    ) -> &mut Self;
+}
+
+impl RegisterTurretType for App {
+    fn register_turret_type(
+        &mut self,
+        turret_type: TurretType,
+        definition: TurretDefinition,
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(TurretTypes::default)
+            .0
+            .insert(turret_type, definition);
+        self
+    }
+}
+
+pub fn machine_gun() -> TurretDefinition {
+    TurretDefinition {
+        name: "Machine gun",
+        cost: 5,
+        health: 200.0,
+        view_range: 350.0,
+        damage: 0.5,
+        bullet_speed: 1_000.0,
+        accuracy: 40.0,
+        fire_delay_secs: 0.1,
+        ammo_capacity: 300.0,
+        bullet_lifetime: 30,
+        pellets: 1,
+        spread: 0.0,
+        modifiers: ProjectileModifiers::default(),
+        sound: Some(SoundEvent::MachineGunShoot),
+        tint: Color::WHITE,
+    }
+}
+
+pub fn cannon() -> TurretDefinition {
+    TurretDefinition {
+        name: "Cannon",
+        cost: 15,
+        health: 300.0,
+        view_range: 450.0,
+        damage: 4.0,
+        bullet_speed: 600.0,
+        accuracy: 10.0,
+        fire_delay_secs: 1.5,
+        ammo_capacity: 20.0,
+        bullet_lifetime: 60,
+        pellets: 1,
+        spread: 0.0,
+        modifiers: ProjectileModifiers {
+            splash: Some(SplashDamage {
+                radius: 64.0,
+                damage: 2.0,
+            }),
+            ..default()
+        },
+        sound: Some(SoundEvent::CannonShoot),
+        tint: Color::rgb(0.6, 0.6, 0.6),
+    }
+}
+
+pub fn flamethrower() -> TurretDefinition {
+    TurretDefinition {
+        name: "Flamethrower",
+        cost: 12,
+        health: 250.0,
+        view_range: 130.0,
+        damage: 0.2,
+        bullet_speed: 400.0,
+        accuracy: 0.0,
+        fire_delay_secs: 0.15,
+        ammo_capacity: 150.0,
+        bullet_lifetime: 20,
+        pellets: 3,
+        spread: 0.6,
+        modifiers: ProjectileModifiers {
+            pierce: 2,
+            on_hit: Some(StatusEffect::burn(0.5, 2.0)),
+            ..default()
+        },
+        sound: Some(SoundEvent::FlamethrowerShoot),
+        tint: Color::rgb(1.0, 0.5, 0.3),
+    }
+}
+
+pub fn slowing_tower() -> TurretDefinition {
+    TurretDefinition {
+        name: "Slowing tower",
+        cost: 10,
+        health: 200.0,
+        view_range: 300.0,
+        damage: 0.1,
+        bullet_speed: 800.0,
+        accuracy: 5.0,
+        fire_delay_secs: 0.5,
+        ammo_capacity: 80.0,
+        bullet_lifetime: 40,
+        pellets: 1,
+        spread: 0.0,
+        modifiers: ProjectileModifiers {
+            on_hit: Some(StatusEffect::slow(0.5, 2.0)),
+            ..default()
+        },
+        sound: Some(SoundEvent::SlowingTowerShoot),
+        tint: Color::rgb(0.5, 0.9, 1.0),
+    }
+}
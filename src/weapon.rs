@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::components::Damage;
+use crate::{bullet_plugin::ProjectileModifiers, components::Damage};
 
 #[derive(Debug, Clone, Component)]
 pub struct Weapon {
@@ -8,6 +8,7 @@ pub struct Weapon {
     pub damage: Damage,
     pub bullet_velocity: f32,
     pub accuracy: f32,
+    pub modifiers: ProjectileModifiers,
 }
 
 impl Weapon {
@@ -17,9 +18,15 @@ impl Weapon {
             bullet_velocity,
             accuracy,
             damage,
+            modifiers: ProjectileModifiers::default(),
         }
     }
 
+    pub fn with_modifiers(mut self, modifiers: ProjectileModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
     fn update(&mut self, dt: &Time) {
         self.delay.tick(dt.delta());
     }
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+/// A streak breaks when no enemy dies for this long.
+const STREAK_WINDOW_SECS: f32 = 3.0;
+/// Kills past this count don't raise the bonus any further.
+const MAX_STREAK_BONUS_KILLS: u32 = 20;
+
+#[derive(Debug, Resource)]
+pub struct KillStreak {
+    pub kills: u32,
+    window: Timer,
+}
+
+impl Default for KillStreak {
+    fn default() -> Self {
+        Self {
+            kills: 0,
+            window: Timer::from_seconds(STREAK_WINDOW_SECS, TimerMode::Once),
+        }
+    }
+}
+
+impl KillStreak {
+    pub fn add_kill(&mut self) {
+        self.kills += 1;
+        self.window.reset();
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        if self.window.tick(delta).just_finished() {
+            self.kills = 0;
+        }
+    }
+
+    /// 5% more XP for every kill in the current streak.
+    pub fn xp_multiplier(&self) -> f32 {
+        1.0 + self.kills.min(MAX_STREAK_BONUS_KILLS) as f32 * 0.05
+    }
+}
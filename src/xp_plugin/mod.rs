@@ -1,85 +1,197 @@
+pub mod kill_streak;
 pub mod xp_level;
 
-use bevy::prelude::*;
+use std::time::Duration;
+
+use bevy::{
+    prelude::*,
+    time::common_conditions::on_timer,
+    utils::{hashbrown::hash_map::Entry, HashMap},
+};
 use bevy_rapier2d::prelude::*;
 
 use crate::{
     asset_loader_plugin::AssetLoader,
-    components::{PickupRange, Player, UiLevelDisplayBar, UiLevelDisplayNumber},
-    events::{SoundEvent, XpDropEvent},
+    components::{
+        Attractable, AttractedToPlayer, PickupType, Player, PlayerPickup, UiLevelDisplayBar,
+        UiLevelDisplayNumber,
+    },
+    difficulty_plugin::Difficulty,
+    events::{EnemyKilledEvent, XpDropEvent},
+    pool::{Pool, RegisterPool},
+    status_effect_plugin::{Stat, StatusEffects},
+    utils::{random_in_range, random_vector},
 };
+use kill_streak::KillStreak;
 use xp_level::XpLevel;
 
+/// Orbs only start merging once there are more than this many on the ground.
+const MERGE_THRESHOLD: usize = 150;
+/// Orbs sharing a cell of this size are merged into one.
+const MERGE_CELL_SIZE: f32 = 64.0;
+/// Orbs further than this from the player are parked in the pool until the player comes back.
+const DORMANT_DISTANCE: f32 = 2_500.0;
+/// Value of an orb drawn at its normal size.
+const BASE_ORB_VALUE: f32 = 100.0;
+
 #[derive(Debug, Clone, Copy, Component, Reflect)]
 pub struct Xp(pub f32);
 
+/// Position and value of the orbs parked for being far from the player.
+#[derive(Debug, Default, Resource)]
+struct DormantXp(Vec<(Vec2, f32)>);
+
 pub struct XpPlugin;
 
 impl Plugin for XpPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<XpDropEvent>();
-        app.add_systems(Update, (spawn_xp, pickup_xp, attract_xp, update_xp_display));
+        app.add_event::<EnemyKilledEvent>();
+        app.init_resource::<KillStreak>();
+        app.init_pool::<Xp>();
+        app.init_resource::<DormantXp>();
+        app.add_systems(
+            Update,
+            (
+                (track_kill_streak, spawn_xp).chain(),
+                scale_xp_orbs,
+                update_xp_display,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (merge_xp_orbs, update_dormant_xp)
+                .chain()
+                .run_if(on_timer(Duration::from_secs(1))),
+        );
         app.register_type::<XpLevel>();
         app.register_type::<Xp>();
     }
 }
 
+fn track_kill_streak(
+    mut streak: ResMut<KillStreak>,
+    mut kill_events: EventReader<EnemyKilledEvent>,
+    time: Res<Time>,
+) {
+    streak.tick(time.delta());
+    for _ in kill_events.read() {
+        streak.add_kill();
+    }
+}
+
 fn spawn_xp(
     mut cmd: Commands,
+    player_q: Query<&StatusEffects, With<Player>>,
     mut xp_drop_event: EventReader<XpDropEvent>,
+    difficulty: Res<Difficulty>,
+    streak: Res<KillStreak>,
+    mut pool: ResMut<Pool<Xp>>,
     asset_loader: Res<AssetLoader>,
 ) {
-    for ev in xp_drop_event.read() {
-        cmd.spawn(Xp(10.))
-            .insert(SpriteBundle {
-                transform: Transform::from_translation(ev.0),
-                texture: asset_loader.xp_sprite.clone(),
-                ..default()
-            })
-            .insert(Velocity::zero())
-            .insert(RigidBody::Dynamic)
-            .insert(Name::new("XP"));
+    let multiplier = difficulty.xp_multiplier()
+        * streak.xp_multiplier()
+        * player_q
+            .get_single()
+            .map_or(1.0, |effects| effects.multiplier(Stat::XpGain));
+
+    for XpDropEvent(position, xp) in xp_drop_event.read() {
+        let scatter = random_vector() * random_in_range(-15.0, 15.0);
+
+        drop_xp(
+            &mut cmd,
+            &mut pool,
+            Xp(xp * multiplier),
+            position.truncate(),
+            scatter.truncate(),
+            asset_loader.xp_sprite.clone(),
+        );
     }
     xp_drop_event.clear();
 }
 
-fn pickup_xp(
+fn merge_xp_orbs(
     mut cmd: Commands,
-    mut player_q: Query<(&Transform, &PickupRange, &mut XpLevel), With<Player>>,
-    xp_q: Query<(&Transform, &Xp, Entity), Without<Player>>,
-    mut sound_event: EventWriter<SoundEvent>,
+    mut xp_q: Query<(&mut Xp, &Transform, Entity), Without<AttractedToPlayer>>,
+    mut pool: ResMut<Pool<Xp>>,
 ) {
-    if let Ok((player, range, mut lvl)) = player_q.get_single_mut() {
-        for (t, xp, e) in xp_q.iter() {
-            if player.translation.distance(t.translation) <= range.0 {
-                lvl.add_xp(*xp);
-                cmd.entity(e).despawn();
-                sound_event.send(SoundEvent::XpPickup);
+    if xp_q.iter().len() <= MERGE_THRESHOLD {
+        return;
+    }
+
+    let mut cells: HashMap<IVec2, (Entity, f32)> = HashMap::default();
+
+    for (xp, t, e) in xp_q.iter() {
+        let cell = (t.translation.truncate() / MERGE_CELL_SIZE)
+            .floor()
+            .as_ivec2();
+
+        match cells.entry(cell) {
+            Entry::Occupied(mut kept) => {
+                kept.get_mut().1 += xp.0;
+                release_xp(&mut cmd, &mut pool, e);
+            }
+            Entry::Vacant(cell) => {
+                cell.insert((e, xp.0));
+            }
+        }
+    }
+
+    for (e, total) in cells.into_values() {
+        if let Ok((mut xp, _, _)) = xp_q.get_mut(e) {
+            if xp.0 != total {
+                xp.0 = total;
             }
         }
     }
 }
 
-fn attract_xp(
-    player_q: Query<(&Transform, &PickupRange), With<Player>>,
-    mut xp_q: Query<(&mut Velocity, &Transform), (With<Xp>, Without<Player>)>,
-    time: Res<Time>,
+fn update_dormant_xp(
+    mut cmd: Commands,
+    player_q: Query<&Transform, With<Player>>,
+    xp_q: Query<(&Xp, &Transform, Entity), (Without<AttractedToPlayer>, Without<Player>)>,
+    mut dormant: ResMut<DormantXp>,
+    mut pool: ResMut<Pool<Xp>>,
+    asset_loader: Res<AssetLoader>,
 ) {
-    if let Ok((player, range)) = player_q.get_single() {
-        let attract_speed = 500.0;
-        let dt = time.delta_seconds();
-        for (mut v, t) in xp_q.iter_mut() {
-            let dist = player.translation.distance(t.translation);
-            if dist <= (range.0 * 2.0) {
-                let vector =
-                    ((player.translation - t.translation).normalize_or_zero() * dt * attract_speed)
-                        * dist;
-                v.linvel = vector.xy();
-            }
+    let Ok(player) = player_q.get_single() else {
+        return;
+    };
+    let player = player.translation.truncate();
+
+    dormant.0.retain(|(position, xp)| {
+        if position.distance(player) > DORMANT_DISTANCE {
+            return true;
+        }
+
+        drop_xp(
+            &mut cmd,
+            &mut pool,
+            Xp(*xp),
+            *position,
+            Vec2::ZERO,
+            asset_loader.xp_sprite.clone(),
+        );
+        false
+    });
+
+    for (xp, t, e) in xp_q.iter() {
+        let position = t.translation.truncate();
+
+        if position.distance(player) > DORMANT_DISTANCE {
+            dormant.0.push((position, xp.0));
+            release_xp(&mut cmd, &mut pool, e);
         }
     }
 }
 
+fn scale_xp_orbs(mut xp_q: Query<(&Xp, &mut Transform), Changed<Xp>>) {
+    for (xp, mut t) in xp_q.iter_mut() {
+        let extra = (xp.0 / BASE_ORB_VALUE - 1.0).max(0.0);
+        t.scale = Vec3::splat((1.0 + extra.sqrt() * 0.5).min(3.0));
+    }
+}
+
 fn update_xp_display(
     player_q: Query<&XpLevel, With<Player>>,
     mut display_lvl_q: Query<&mut Text, With<UiLevelDisplayNumber>>,
@@ -94,17 +206,39 @@ fn update_xp_display(
     }
 }
 
-pub fn drop_xp(cmd: &mut Commands, xp: Xp, position: Vec2, velocity: Vec2, texture: Handle<Image>) {
+/// Reuses a parked orb from `pool` when there is one, spawns a new one otherwise.
+pub fn drop_xp(
+    cmd: &mut Commands,
+    pool: &mut Pool<Xp>,
+    xp: Xp,
+    position: Vec2,
+    velocity: Vec2,
+    texture: Handle<Image>,
+) {
+    let orb = (xp, PlayerPickup(PickupType::Xp), Velocity::linear(velocity));
+
+    if let Some(mut orb_entity) = pool.acquire(cmd) {
+        orb_entity.insert((orb, Transform::from_translation(position.extend(10.0))));
+        return;
+    }
+
     cmd.spawn((
-        xp,
+        orb,
+        Attractable::default(),
         SpriteBundle {
             transform: Transform::from_translation(position.extend(10.0)),
             texture,
             ..default()
         },
-        Velocity::linear(velocity),
         Restitution::coefficient(2.0),
         RigidBody::Dynamic,
         Name::new("Xp"),
     ));
 }
+
+/// Parks a collected or merged orb in `pool` instead of despawning it.
+pub fn release_xp(cmd: &mut Commands, pool: &mut Pool<Xp>, orb: Entity) {
+    if let Some(mut orb) = pool.release(cmd, orb) {
+        orb.remove::<(PlayerPickup, AttractedToPlayer)>();
+    }
+}
@@ -7,6 +7,8 @@ pub struct XpLevel {
     pub xp: f32,
     pub xp_to_next: f32,
     pub level: u32,
+    /// Everything collected this run, across levels.
+    pub total: f32,
 }
 
 impl XpLevel {
@@ -16,11 +18,13 @@ impl XpLevel {
             xp: 0.,
             xp_to_next,
             level,
+            total: 0.,
         }
     }
 
     pub fn add_xp(&mut self, Xp(xp): Xp) {
         let xp_now = xp + self.xp;
+        self.total += xp;
 
         self.level += (xp_now / self.xp_to_next) as u32;
         self.xp = xp_now % self.xp_to_next;
@@ -0,0 +1,75 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use survival::{
+    bullet_plugin::BulletFiredEvent,
+    components::{Damage, Health, LifeTime},
+    run_stats_plugin::RunStats,
+    turret_plugin::{turret_types::TurretType, SpawnTurretEvent, TurretAmmo},
+    xp_plugin::Xp,
+};
+
+#[test]
+fn bullets_kill_enemies_and_drop_xp() {
+    let mut app = test_app();
+    let spawner = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    let enemy = spawn_enemy(&mut app, Vec2::new(250.0, 0.0), spawner);
+    step(&mut app, 1);
+
+    app.world.send_event(BulletFiredEvent {
+        from: Vec2::new(100.0, 0.0),
+        at: Vec2::new(250.0, 0.0),
+        acc: 0.0,
+        dmg: Damage(100.0),
+        lifetime: LifeTime(60),
+        bullet_speed: 600.0,
+        modifiers: default(),
+    });
+    step(&mut app, 40);
+
+    assert!(!exists(&app, enemy));
+    assert!(count::<With<Xp>>(&mut app) > 0);
+    assert_eq!(app.world.resource::<RunStats>().kills, 1);
+}
+
+#[test]
+fn turrets_fire_at_enemies_in_range() {
+    let mut app = test_app();
+    app.world.send_event(SpawnTurretEvent(
+        Vec2::new(0.0, 400.0),
+        TurretType::MachineGun,
+    ));
+    step(&mut app, 1);
+
+    let spawner = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    let enemy = spawn_enemy(&mut app, Vec2::new(150.0, 400.0), spawner);
+    step_secs(&mut app, 1.0);
+
+    let ammo = *app.world.query::<&TurretAmmo>().single(&app.world);
+    assert!(ammo.current < ammo.max);
+
+    // A dead enemy was hit as well.
+    let hurt = match app.world.get::<Health>(enemy) {
+        Some(hp) => hp.0 < hp.1,
+        None => true,
+    };
+    assert!(hurt);
+}
+
+#[test]
+fn turrets_ignore_enemies_out_of_range() {
+    let mut app = test_app();
+    app.world.send_event(SpawnTurretEvent(
+        Vec2::new(0.0, 400.0),
+        TurretType::MachineGun,
+    ));
+    step(&mut app, 1);
+
+    let spawner = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    spawn_enemy(&mut app, Vec2::new(1_000.0, 400.0), spawner);
+    step_secs(&mut app, 1.0);
+
+    let ammo = *app.world.query::<&TurretAmmo>().single(&app.world);
+    assert_eq!(ammo.current, ammo.max);
+}
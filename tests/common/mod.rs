@@ -0,0 +1,107 @@
+//! Harness shared by the integration tests: the gameplay on a headless app with the randomly
+//! generated world cleared away, plus helpers to spawn things and step frames.
+
+// Every test binary compiles this module but only uses some of it.
+#![allow(dead_code)]
+
+use bevy::{
+    ecs::{query::QueryFilter, system::CommandQueue},
+    prelude::*,
+};
+use survival::{
+    asset_loader_plugin::AssetLoader,
+    components::{Enemy, Player, PlayerPickup},
+    headless_app,
+    spawner_plugin::{self, Spawner, SpawnerSpawnTimer},
+    tree_plugin::TreeTrunk,
+};
+
+pub const FPS: u32 = 60;
+
+/// The full gameplay after its first frame, with the generated trees, spawners, enemies and
+/// pickups despawned so tests only deal with what they spawn themselves.
+///
+/// Spawners and pickups keep trickling in far away, which only matters for tests that run for
+/// longer than a spawner's timer.
+pub fn test_app() -> App {
+    let mut app = headless_app(FPS);
+    app.update();
+
+    despawn_all::<With<TreeTrunk>>(&mut app);
+    despawn_all::<With<Spawner>>(&mut app);
+    despawn_all::<With<Enemy>>(&mut app);
+    despawn_all::<With<PlayerPickup>>(&mut app);
+    app.update();
+
+    app
+}
+
+pub fn step(app: &mut App, frames: u32) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+pub fn step_secs(app: &mut App, secs: f32) {
+    step(app, (secs * FPS as f32).ceil() as u32);
+}
+
+/// Runs `f` with `Commands` and the assets, like a system would, and applies the commands.
+pub fn with_commands<R>(app: &mut App, f: impl FnOnce(&mut Commands, &AssetLoader) -> R) -> R {
+    let assets = app.world.resource::<AssetLoader>().clone();
+    let mut queue = CommandQueue::default();
+
+    let result = f(&mut Commands::new(&mut queue, &app.world), &assets);
+    queue.apply(&mut app.world);
+    result
+}
+
+pub fn despawn_all<F: QueryFilter>(app: &mut App) {
+    let entities: Vec<Entity> = app
+        .world
+        .query_filtered::<Entity, F>()
+        .iter(&app.world)
+        .collect();
+
+    for e in entities {
+        if let Some(entity) = app.world.get_entity_mut(e) {
+            entity.despawn_recursive();
+        }
+    }
+}
+
+pub fn count<F: QueryFilter>(app: &mut App) -> usize {
+    app.world.query_filtered::<(), F>().iter(&app.world).count()
+}
+
+pub fn exists(app: &App, e: Entity) -> bool {
+    app.world.get_entity(e).is_some()
+}
+
+pub fn player(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<Entity, With<Player>>()
+        .single(&app.world)
+}
+
+/// A spawner whose timer won't go off during a test, enemies are added with `spawn_enemy`.
+pub fn spawn_spawner(app: &mut App, pos: Vec2) -> Entity {
+    app.world
+        .spawn((
+            Spawner,
+            SpawnerSpawnTimer {
+                timer: Timer::from_seconds(1_000.0, TimerMode::Repeating),
+                spawn_limit: 16,
+                alive_now: 0,
+            },
+            TransformBundle::from_transform(Transform::from_translation(pos.extend(0.0))),
+        ))
+        .id()
+}
+
+/// An enemy counted towards `spawner` from the next frame on, like the ones it spawns itself.
+pub fn spawn_enemy(app: &mut App, pos: Vec2, spawner: Entity) -> Entity {
+    with_commands(app, |cmd, assets| {
+        spawner_plugin::spawn_enemy(cmd, pos.extend(0.0), spawner, assets)
+    })
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use survival::{
+    components::AttractedToPlayer,
+    events::XpDropEvent,
+    status_effect_plugin::{ApplyStatusEffectEvent, StatusEffect, StatusEffectKind},
+    xp_plugin::{xp_level::XpLevel, Xp},
+};
+
+fn xp_orb(app: &mut App) -> Entity {
+    app.world
+        .query_filtered::<Entity, With<Xp>>()
+        .single(&app.world)
+}
+
+#[test]
+fn magnets_attract_xp() {
+    let mut app = test_app();
+    let player = player(&mut app);
+    let xp_before = app.world.get::<XpLevel>(player).unwrap().total;
+
+    app.world
+        .send_event(XpDropEvent(Vec3::new(800.0, 0.0, 0.0), 50.0));
+    step(&mut app, 1);
+    let orb = xp_orb(&mut app);
+
+    app.world.send_event(ApplyStatusEffectEvent {
+        target: player,
+        effect: StatusEffect::new(StatusEffectKind::Magnetism, 0.0, 10.0),
+    });
+    step(&mut app, 2);
+    assert!(app.world.get::<AttractedToPlayer>(orb).is_some());
+
+    step_secs(&mut app, 2.0);
+    assert_eq!(count::<With<Xp>>(&mut app), 0);
+    assert!(app.world.get::<XpLevel>(player).unwrap().total > xp_before);
+}
+
+#[test]
+fn xp_out_of_range_stays_put_without_a_magnet() {
+    let mut app = test_app();
+
+    app.world
+        .send_event(XpDropEvent(Vec3::new(800.0, 0.0, 0.0), 50.0));
+    step(&mut app, 1);
+    let orb = xp_orb(&mut app);
+
+    step_secs(&mut app, 2.0);
+    assert!(app.world.get::<Xp>(orb).is_some());
+    assert!(app.world.get::<AttractedToPlayer>(orb).is_none());
+}
+
+#[test]
+fn pickup_range_boost_widens_the_pickup_radius() {
+    let mut app = test_app();
+    let player = player(&mut app);
+
+    app.world
+        .send_event(XpDropEvent(Vec3::new(150.0, 0.0, 0.0), 50.0));
+    step(&mut app, 1);
+    let orb = xp_orb(&mut app);
+
+    step_secs(&mut app, 1.0);
+    assert!(app.world.get::<Xp>(orb).is_some());
+
+    app.world.send_event(ApplyStatusEffectEvent {
+        target: player,
+        effect: StatusEffect::new(StatusEffectKind::PickupRangeBoost, 2.0, 10.0),
+    });
+    step_secs(&mut app, 2.0);
+    assert_eq!(count::<With<Xp>>(&mut app), 0);
+}
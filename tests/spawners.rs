@@ -0,0 +1,221 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use survival::{
+    biome_plugin::BiomeMap,
+    components::Enemy,
+    difficulty_plugin::Difficulty,
+    events::DamageEvent,
+    spawner_plugin::{Spawner, SpawnerConfig, SpawnerId, SpawnerSpawnTimer},
+    turret_plugin::{turret_types::TurretType, SpawnTurretEvent},
+};
+
+fn alive_now(app: &App, spawner: Entity) -> u32 {
+    app.world
+        .get::<SpawnerSpawnTimer>(spawner)
+        .unwrap()
+        .alive_now
+}
+
+fn owned_by(app: &mut App, spawner: Entity) -> u32 {
+    app.world
+        .query::<&SpawnerId>()
+        .iter(&app.world)
+        .filter(|id| id.0 == spawner)
+        .count() as u32
+}
+
+#[test]
+fn killed_enemies_free_their_spawner_slot() {
+    let mut app = test_app();
+    let spawner = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    let enemy = spawn_enemy(&mut app, Vec2::new(300.0, 0.0), spawner);
+    spawn_enemy(&mut app, Vec2::new(-300.0, 0.0), spawner);
+    step(&mut app, 1);
+    assert_eq!(alive_now(&app, spawner), 2);
+
+    app.world.send_event(DamageEvent {
+        target: enemy,
+        amount: 100.0,
+        from: None,
+    });
+    step(&mut app, 2);
+
+    assert!(!exists(&app, enemy));
+    assert_eq!(alive_now(&app, spawner), 1);
+}
+
+#[test]
+fn enemies_despawned_any_other_way_free_their_spawner_slot() {
+    let mut app = test_app();
+    let spawner = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    let despawned = spawn_enemy(&mut app, Vec2::new(300.0, 0.0), spawner);
+    let recursive = spawn_enemy(&mut app, Vec2::new(-300.0, 0.0), spawner);
+    let released = spawn_enemy(&mut app, Vec2::new(0.0, 300.0), spawner);
+    step(&mut app, 1);
+    assert_eq!(alive_now(&app, spawner), 3);
+
+    app.world.despawn(despawned);
+    app.world.entity_mut(recursive).despawn_recursive();
+    app.world.entity_mut(released).remove::<SpawnerId>();
+    step(&mut app, 1);
+
+    assert_eq!(alive_now(&app, spawner), 0);
+}
+
+#[test]
+fn spawned_enemies_are_counted_and_replaced() {
+    let mut app = test_app();
+    let spawner = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    {
+        let mut timer = app.world.get_mut::<SpawnerSpawnTimer>(spawner).unwrap();
+        timer.timer = Timer::from_seconds(0.1, TimerMode::Repeating);
+        timer.spawn_limit = 3;
+    }
+
+    step_secs(&mut app, 1.0);
+    assert_eq!(owned_by(&mut app, spawner), 3);
+    assert_eq!(alive_now(&app, spawner), 3);
+
+    let some_enemy = app
+        .world
+        .query_filtered::<(Entity, &SpawnerId), With<Enemy>>()
+        .iter(&app.world)
+        .find(|(_, id)| id.0 == spawner)
+        .map(|(e, _)| e)
+        .unwrap();
+    app.world.despawn(some_enemy);
+
+    step_secs(&mut app, 1.0);
+    assert!(!exists(&app, some_enemy));
+    assert_eq!(owned_by(&mut app, spawner), 3);
+    assert_eq!(alive_now(&app, spawner), 3);
+}
+
+#[test]
+fn moving_an_enemy_to_another_spawner_moves_its_slot() {
+    let mut app = test_app();
+    let first = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    let second = spawn_spawner(&mut app, Vec2::new(0.0, 3_000.0));
+    let enemy = spawn_enemy(&mut app, Vec2::new(300.0, 0.0), first);
+    step(&mut app, 1);
+
+    app.world.entity_mut(enemy).insert(SpawnerId(second));
+    step(&mut app, 1);
+
+    assert_eq!(alive_now(&app, first), 0);
+    assert_eq!(alive_now(&app, second), 1);
+}
+
+#[test]
+fn destroyed_spawners_leave_no_orphans() {
+    let mut app = test_app();
+    let spawner = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    let enemy = spawn_enemy(&mut app, Vec2::new(300.0, 0.0), spawner);
+    step(&mut app, 1);
+
+    app.world.despawn(spawner);
+    step(&mut app, 2);
+
+    assert!(exists(&app, enemy));
+    assert!(app.world.get::<SpawnerId>(enemy).is_none());
+}
+
+#[test]
+fn enemies_of_a_missing_spawner_are_not_owned() {
+    let mut app = test_app();
+    let spawner = spawn_spawner(&mut app, Vec2::new(0.0, -3_000.0));
+    app.world.despawn(spawner);
+    step(&mut app, 1);
+
+    let enemy = spawn_enemy(&mut app, Vec2::new(300.0, 0.0), spawner);
+    step(&mut app, 2);
+
+    assert!(app.world.get::<SpawnerId>(enemy).is_none());
+}
+
+fn spawner_positions(app: &mut App) -> Vec<Vec2> {
+    app.world
+        .query_filtered::<&Transform, With<Spawner>>()
+        .iter(&app.world)
+        .map(|t| t.translation.truncate())
+        .collect()
+}
+
+fn player_position(app: &mut App) -> Vec2 {
+    let player = player(app);
+    app.world
+        .get::<Transform>(player)
+        .unwrap()
+        .translation
+        .truncate()
+}
+
+#[test]
+fn spawners_follow_the_placement_rules() {
+    let mut app = test_app();
+    let turret = Vec2::new(1_500.0, 0.0);
+    app.world
+        .send_event(SpawnTurretEvent(turret, TurretType::MachineGun));
+    step(&mut app, 1);
+    // Only spawners placed after the turret stood have to keep clear of it.
+    despawn_all::<With<Spawner>>(&mut app);
+    step_secs(&mut app, 2.0);
+
+    let config = app.world.resource::<SpawnerConfig>().clone();
+    let biomes = *app.world.resource::<BiomeMap>();
+    let player = player_position(&mut app);
+    let spawners = spawner_positions(&mut app);
+    assert_eq!(spawners.len(), config.count_for_level(0));
+
+    for (i, pos) in spawners.iter().enumerate() {
+        let from_player = pos.distance(player);
+        assert!(from_player >= config.min_player_distance - 1.0);
+        assert!(from_player <= config.max_player_distance + 1.0);
+        assert!(pos.distance(turret) >= config.base_radius);
+        assert!(config.biomes.contains(&biomes.biome_at(*pos)));
+
+        for other in spawners.iter().skip(i + 1) {
+            assert!(pos.distance(*other) >= config.min_spacing);
+        }
+    }
+}
+
+#[test]
+fn spawner_count_follows_difficulty() {
+    let mut app = test_app();
+    step_secs(&mut app, 1.0);
+    let config = app.world.resource::<SpawnerConfig>().clone();
+    assert_eq!(count::<With<Spawner>>(&mut app), config.count_for_level(0));
+
+    app.world.resource_mut::<Difficulty>().level = 5;
+    step_secs(&mut app, 2.0);
+
+    assert!(config.count_for_level(5) > config.count_for_level(0));
+    assert_eq!(count::<With<Spawner>>(&mut app), config.count_for_level(5));
+}
+
+#[test]
+fn far_spawners_are_culled_and_replaced() {
+    let mut app = test_app();
+    step_secs(&mut app, 1.0);
+    let config = app.world.resource::<SpawnerConfig>().clone();
+    let expected = count::<With<Spawner>>(&mut app);
+
+    let far = spawn_spawner(&mut app, Vec2::new(config.cull_distance + 1_000.0, 0.0));
+    let enemy = spawn_enemy(&mut app, Vec2::new(300.0, 0.0), far);
+    step(&mut app, 3);
+
+    assert!(!exists(&app, far));
+    assert!(exists(&app, enemy));
+    assert!(app.world.get::<SpawnerId>(enemy).is_none());
+
+    step_secs(&mut app, 1.0);
+    let player = player_position(&mut app);
+    let spawners = spawner_positions(&mut app);
+    assert_eq!(spawners.len(), expected);
+    for pos in spawners {
+        assert!(pos.distance(player) <= config.cull_distance);
+    }
+}
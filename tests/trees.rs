@@ -0,0 +1,110 @@
+mod common;
+
+use bevy::prelude::*;
+use common::*;
+use survival::{
+    bullet_plugin::BulletFiredEvent,
+    components::{Bullet, Damage, Health, Inventory, ItemType, LifeTime, PickupType, PlayerPickup},
+    events::DamageEvent,
+    input_map_plugin::{ActionState, InputAction},
+    run_stats_plugin::RunStats,
+    tree_plugin::{spawn_tree_trunk, Tree, TreeGrowth, TreeTrunk},
+};
+
+/// A grown tree at `pos`, returns the trunk and the tree on it.
+fn spawn_tree(app: &mut App, pos: Vec2) -> (Entity, Entity) {
+    let trunk = with_commands(app, |cmd, assets| {
+        spawn_tree_trunk(cmd, pos.extend(7.0), TreeGrowth::Grown, assets)
+    });
+    step(app, 1);
+
+    let tree = app
+        .world
+        .query_filtered::<(Entity, &Parent), With<Tree>>()
+        .iter(&app.world)
+        .find(|(_, parent)| parent.get() == trunk)
+        .map(|(e, _)| e)
+        .expect("grown trunks have a tree");
+
+    (trunk, tree)
+}
+
+#[test]
+fn trees_fall_and_drop_wood() {
+    let mut app = test_app();
+    let (trunk, tree) = spawn_tree(&mut app, Vec2::new(600.0, 0.0));
+
+    app.world.send_event(DamageEvent {
+        target: tree,
+        amount: 1_000.0,
+        from: None,
+    });
+    step_secs(&mut app, 2.0);
+
+    assert!(!exists(&app, tree));
+    assert!(matches!(
+        app.world.get::<TreeGrowth>(trunk),
+        Some(TreeGrowth::Stump(_))
+    ));
+
+    let wood = app
+        .world
+        .query::<&PlayerPickup>()
+        .iter(&app.world)
+        .filter(|p| matches!(p.0, PickupType::Item(ItemType::Wood)))
+        .count();
+    assert!(wood > 0);
+    assert_eq!(app.world.resource::<RunStats>().trees_chopped, 1);
+}
+
+#[test]
+fn bullets_stop_on_trunks_and_damage_the_tree() {
+    let mut app = test_app();
+    let (_, tree) = spawn_tree(&mut app, Vec2::new(300.0, 0.0));
+
+    app.world.send_event(BulletFiredEvent {
+        from: Vec2::new(100.0, 0.0),
+        at: Vec2::new(300.0, 0.0),
+        acc: 0.0,
+        dmg: Damage(40.0),
+        lifetime: LifeTime(60),
+        bullet_speed: 600.0,
+        modifiers: default(),
+    });
+    step(&mut app, 40);
+
+    let hp = app.world.get::<Health>(tree).unwrap();
+    assert!(hp.0 < hp.1);
+    assert_eq!(count::<With<Bullet>>(&mut app), 0);
+}
+
+#[test]
+fn planting_a_sapling_leaves_the_player_free_to_move() {
+    let mut app = test_app();
+    let player = player(&mut app);
+    let start = app.world.get::<Transform>(player).unwrap().translation;
+
+    app.world
+        .get_mut::<Inventory>(player)
+        .unwrap()
+        .add(ItemType::Sapling, 1);
+    let mut actions = app.world.resource_mut::<ActionState>();
+    actions.aim_at(start.truncate() + Vec2::new(0.0, 200.0));
+    actions.press(InputAction::Plant);
+    step(&mut app, 1);
+    app.world.resource_mut::<ActionState>().release_all();
+    step(&mut app, 1);
+
+    let sapling = app
+        .world
+        .query_filtered::<&Transform, With<TreeTrunk>>()
+        .single(&app.world)
+        .translation;
+    assert!(sapling.truncate().distance(start.truncate()) > 64.0);
+
+    app.world.resource_mut::<ActionState>().movement = Vec2::X;
+    step_secs(&mut app, 1.0);
+
+    let end = app.world.get::<Transform>(player).unwrap().translation;
+    assert!(end.x - start.x > 50.0);
+}